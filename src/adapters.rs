@@ -0,0 +1,1258 @@
+use std::{collections::VecDeque, io::Read, ops::Range};
+
+use crate::{
+    CharStream, CharacterError, CharacterIterator, CharacterStream, CharacterStreamResult,
+    LineSplitter, LineStep, Position,
+};
+
+/// Iterator over the fields of a [CharacterIterator] separated by a delimiter.
+///
+/// Created by [CharacterIterator::split].
+pub struct Split<Stream: CharStream> {
+    /// The iterator the fields are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// The character that separates fields.
+    pub(crate) delimiter: char,
+    /// Whether or not the final field has been yielded.
+    pub(crate) finished: bool,
+}
+
+impl<Stream: CharStream> Split<Stream> {
+    /// Create a [Split] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>, delimiter: char) -> Self {
+        Self {
+            iter,
+            delimiter,
+            finished: false,
+        }
+    }
+}
+
+impl<Stream: CharStream> Iterator for Split<Stream> {
+    type Item = Result<String, CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut field = String::new();
+
+        loop {
+            match self.iter.next() {
+                Some(Ok(character)) if character == self.delimiter => return Some(Ok(field)),
+                Some(Ok(character)) => field.push(character),
+                Some(Err(error)) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+                None => {
+                    self.finished = true;
+                    return Some(Ok(field));
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the fields of a [CharacterIterator] separated by a string.
+///
+/// Created by [CharacterIterator::split_str].
+pub struct SplitStr<Stream: CharStream> {
+    /// The iterator the fields are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// The string that separates fields. An empty delimiter never matches.
+    pub(crate) delimiter: String,
+    /// Whether or not the final field has been yielded.
+    pub(crate) finished: bool,
+}
+
+impl<Stream: CharStream> SplitStr<Stream> {
+    /// Create a [SplitStr] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>, delimiter: &str) -> Self {
+        Self {
+            iter,
+            delimiter: delimiter.to_string(),
+            finished: false,
+        }
+    }
+}
+
+impl<Stream: CharStream> Iterator for SplitStr<Stream> {
+    type Item = Result<String, CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut field = String::new();
+
+        loop {
+            match self.iter.next() {
+                Some(Ok(character)) => {
+                    field.push(character);
+                    if !self.delimiter.is_empty() && field.ends_with(&self.delimiter) {
+                        field.truncate(field.len() - self.delimiter.len());
+                        return Some(Ok(field));
+                    }
+                }
+                Some(Err(error)) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+                None => {
+                    self.finished = true;
+                    return Some(Ok(field));
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the whitespace separated words of a [CharacterIterator].
+///
+/// Created by [CharacterIterator::words].
+pub struct Words<Stream: CharStream> {
+    /// The iterator the words are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// Whether or not an error or the end of the stream has been reached.
+    pub(crate) finished: bool,
+}
+
+impl<Stream: CharStream> Words<Stream> {
+    /// Create a [Words] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>) -> Self {
+        Self {
+            iter,
+            finished: false,
+        }
+    }
+}
+
+impl<Stream: CharStream> Iterator for Words<Stream> {
+    type Item = Result<String, CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut word = String::new();
+
+        loop {
+            match self.iter.next() {
+                Some(Ok(character)) if character.is_whitespace() => {
+                    if !word.is_empty() {
+                        return Some(Ok(word));
+                    }
+                }
+                Some(Ok(character)) => word.push(character),
+                Some(Err(error)) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+                None => {
+                    self.finished = true;
+                    return (!word.is_empty()).then_some(Ok(word));
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the lines of a [CharacterIterator].
+///
+/// Lines end at `\n`, `\r\n` or a lone `\r`, which isn't included in the yielded line. Errors are
+/// wrapped in a [Line](CharacterError::Line) error with the number of the line they occurred on,
+/// starting at 1, and end the iteration.
+///
+/// Created by [CharacterIterator::lines].
+pub struct Lines<Stream: CharStream> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// The number of the next line.
+    pub(crate) line: usize,
+    /// Whether or not an error or the end of the stream has been reached.
+    pub(crate) finished: bool,
+    /// Where the current line ends.
+    pub(crate) splitter: LineSplitter,
+}
+
+impl<Stream: CharStream> Lines<Stream> {
+    /// Create a [Lines] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>) -> Self {
+        Self {
+            iter,
+            line: 1,
+            finished: false,
+            splitter: LineSplitter::default(),
+        }
+    }
+}
+
+impl<Stream: CharStream> Iterator for Lines<Stream> {
+    type Item = Result<String, CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut line = String::new();
+
+        loop {
+            match self.iter.next() {
+                Some(Ok(character)) => match self.splitter.feed(character) {
+                    LineStep::Char(character) => line.push(character),
+                    LineStep::End(_) => break,
+                    LineStep::Skip => {}
+                },
+                Some(Err(error)) => {
+                    self.finished = true;
+                    return Some(Err(CharacterError::Line {
+                        line: self.line,
+                        source: Box::new(error),
+                    }));
+                }
+                None => {
+                    self.finished = true;
+                    if line.is_empty() {
+                        return None;
+                    }
+                    break;
+                }
+            }
+        }
+
+        self.line += 1;
+        Some(Ok(line))
+    }
+}
+
+/// Iterator over the characters of a [CharacterStream] paired with the bytes they were decoded from.
+///
+/// Created by [CharacterIterator::with_raw_bytes].
+pub struct RawBytes<Reader: Read> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<CharacterStream<Reader>>,
+}
+
+impl<Reader: Read> RawBytes<Reader> {
+    /// Create a [RawBytes] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<CharacterStream<Reader>>) -> Self {
+        Self { iter }
+    }
+}
+
+impl<Reader: Read> Iterator for RawBytes<Reader> {
+    type Item = Result<(char, Vec<u8>), CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_with(|stream| stream.read_char_with_bytes())
+    }
+}
+
+/// Iterator that attaches a context label to the decode errors of a [CharacterIterator].
+///
+/// Every error that is about the bytes of a character is wrapped in a
+/// [Context](CharacterError::Context) error, IO and peeking errors are passed through.
+///
+/// Created by [CharacterIterator::context].
+pub struct Contextualized<Stream: CharStream> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// The label attached to errors.
+    pub(crate) label: &'static str,
+}
+
+impl<Stream: CharStream> Contextualized<Stream> {
+    /// Create a [Contextualized] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>, label: &'static str) -> Self {
+        Self { iter, label }
+    }
+}
+
+impl<Stream: CharStream> Iterator for Contextualized<Stream> {
+    type Item = CharacterStreamResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.iter.next()? {
+            Err(error) if error.is_decode_error() => Err(CharacterError::Context {
+                label: self.label,
+                source: Box::new(error),
+            }),
+            other => other,
+        })
+    }
+}
+
+/// Iterator that collapses runs of whitespace from a [CharacterIterator] into a single space.
+///
+/// Created by [CharacterIterator::collapse_whitespace].
+pub struct CollapseWhitespace<Stream: CharStream> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// The item that ended the last run of whitespace.
+    pub(crate) pending: Option<CharacterStreamResult>,
+}
+
+impl<Stream: CharStream> CollapseWhitespace<Stream> {
+    /// Create a [CollapseWhitespace] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>) -> Self {
+        Self {
+            iter,
+            pending: None,
+        }
+    }
+}
+
+impl<Stream: CharStream> Iterator for CollapseWhitespace<Stream> {
+    type Item = CharacterStreamResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.pending.take().or_else(|| self.iter.next())? {
+            Ok(character) if character.is_whitespace() => {
+                self.pending = self
+                    .iter
+                    .by_ref()
+                    .find(|item| !matches!(item, Ok(character) if character.is_whitespace()));
+
+                Some(Ok(' '))
+            }
+            other => Some(other),
+        }
+    }
+}
+
+/// Iterator that converts the newlines of a [CharacterIterator] into `\n`.
+///
+/// It is a [CharStream] as well, so peeking, position tracking and the other adapters can be
+/// layered on top of it.
+///
+/// Created by [CharacterIterator::normalize_newlines].
+pub struct NormalizeNewlines<Stream: CharStream> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// The item read after a `\r` that was not a `\n`.
+    pub(crate) pending: Option<CharacterStreamResult>,
+    /// Whether or not a `\r` that is not followed by a `\n` is left as is.
+    pub preserve_lone_cr: bool,
+}
+
+impl<Stream: CharStream> NormalizeNewlines<Stream> {
+    /// Create a [NormalizeNewlines] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>) -> Self {
+        Self {
+            iter,
+            pending: None,
+            preserve_lone_cr: false,
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Only convert `\r\n`, leaving a `\r` that is not followed by a `\n` as is.
+    pub fn preserve_lone_cr(mut self, preserve_lone_cr: bool) -> Self {
+        self.preserve_lone_cr = preserve_lone_cr;
+        self
+    }
+}
+
+impl<Stream: CharStream> CharStream for NormalizeNewlines<Stream> {
+    fn read_char(&mut self) -> CharacterStreamResult {
+        let next = self.pending.take().or_else(|| self.iter.next());
+
+        match next.unwrap_or(Err(CharacterError::NoBytesRead)) {
+            Ok('\r') => match self.iter.next() {
+                Some(Ok('\n')) => Ok('\n'),
+                next => {
+                    self.pending = next;
+                    Ok(if self.preserve_lone_cr { '\r' } else { '\n' })
+                }
+            },
+            other => other,
+        }
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.iter.stream.is_lossy()
+    }
+}
+
+impl<Stream: CharStream> Iterator for NormalizeNewlines<Stream> {
+    type Item = CharacterStreamResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_char() {
+            Err(CharacterError::NoBytesRead) => None,
+            result => Some(result),
+        }
+    }
+}
+
+/// An item produced by [Indented].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndentEvent {
+    /// A character that isn't part of the indentation or a line ending.
+    Char(char),
+    /// The end of a line.
+    Newline,
+    /// A line is indented deeper than the previous one.
+    Indent,
+    /// A line closes an indentation level. Several may follow each other.
+    Dedent,
+}
+
+/// Iterator that replaces the leading whitespace of each line of a [CharacterIterator] with
+/// [Indent](IndentEvent::Indent) and [Dedent](IndentEvent::Dedent) events.
+///
+/// Lines only containing whitespace don't affect the indentation. A dedent to a width between two
+/// levels closes the deeper level and opens a new one. Lines end at `\n`, `\r\n` or a lone `\r`,
+/// each yielding a single [Newline](IndentEvent::Newline).
+///
+/// Created by [CharacterIterator::indented].
+pub struct Indented<Stream: CharStream> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// The width of the open indentation levels, innermost last.
+    pub(crate) levels: Vec<usize>,
+    /// Items to yield before reading further.
+    pub(crate) pending: VecDeque<Result<IndentEvent, CharacterError>>,
+    /// Whether the next character starts a line.
+    pub(crate) at_line_start: bool,
+    /// Whether the remaining levels have been closed at the end of the stream.
+    pub(crate) finished: bool,
+    /// Where the current line ends.
+    pub(crate) splitter: LineSplitter,
+    /// The distance between tab stops, in columns. At least 1, see [tab_width](Indented::tab_width).
+    pub(crate) tab_width: usize,
+}
+
+impl<Stream: CharStream> Indented<Stream> {
+    /// Create an [Indented] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>) -> Self {
+        Self {
+            iter,
+            levels: vec![],
+            pending: VecDeque::new(),
+            at_line_start: true,
+            finished: false,
+            splitter: LineSplitter::default(),
+            tab_width: 8,
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Place tab stops every `tab_width` columns. A `tab_width` of 1 counts a tab like a space.
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width.max(1);
+        self
+    }
+
+    /// Queue the events that move the indentation to `width`.
+    fn indent_to(&mut self, width: usize) {
+        while self.levels.last().is_some_and(|level| *level > width) {
+            self.levels.pop();
+            self.pending.push_back(Ok(IndentEvent::Dedent));
+        }
+
+        if width > self.levels.last().copied().unwrap_or(0) {
+            self.levels.push(width);
+            self.pending.push_back(Ok(IndentEvent::Indent));
+        }
+    }
+
+    /// Read the next character, skipping the `\n` of a `\r\n` that already ended a line.
+    fn read_step(&mut self) -> Option<Result<LineStep, CharacterError>> {
+        loop {
+            match self.iter.next()? {
+                Ok(character) => match self.splitter.feed(character) {
+                    LineStep::Skip => continue,
+                    step => return Some(Ok(step)),
+                },
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+
+    /// Queue the dedents that close every level.
+    fn finish(&mut self) {
+        self.finished = true;
+        self.indent_to(0);
+    }
+}
+
+impl<Stream: CharStream> Iterator for Indented<Stream> {
+    type Item = Result<IndentEvent, CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            if self.finished {
+                return None;
+            }
+
+            let next = if self.at_line_start {
+                self.at_line_start = false;
+
+                let mut width = 0;
+                let next = loop {
+                    match self.read_step() {
+                        Some(Ok(LineStep::Char(' '))) => width += 1,
+                        Some(Ok(LineStep::Char('\t'))) => {
+                            width = (width / self.tab_width + 1) * self.tab_width
+                        }
+                        other => break other,
+                    }
+                };
+
+                if matches!(next, Some(Ok(LineStep::Char(_)))) {
+                    self.indent_to(width);
+                }
+
+                next
+            } else {
+                self.read_step()
+            };
+
+            match next {
+                Some(Ok(LineStep::Char(character))) => {
+                    self.pending.push_back(Ok(IndentEvent::Char(character)))
+                }
+                Some(Ok(LineStep::End(_) | LineStep::Skip)) => {
+                    self.at_line_start = true;
+                    self.pending.push_back(Ok(IndentEvent::Newline));
+                }
+                Some(Err(error)) => return Some(Err(error)),
+                None => self.finish(),
+            }
+        }
+    }
+}
+
+/// Iterator that removes comments from a [CharacterIterator], pairing each remaining character
+/// with the [Position] it had in the original text.
+///
+/// Line comments run up to, but not including, the next `\n`. No comment markers are recognized
+/// until they are added with [line_comment](StripComments::line_comment) and
+/// [block_comment](StripComments::block_comment).
+///
+/// Created by [CharacterIterator::strip_comments].
+pub struct StripComments<Stream: CharStream> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<Stream>,
+    /// Characters read ahead to look for comment markers.
+    pub(crate) lookahead: VecDeque<CharacterStreamResult>,
+    /// The position of the first character in `lookahead`.
+    pub(crate) position: Position,
+    /// The markers that start a line comment.
+    pub line_comments: Vec<String>,
+    /// The markers that open and close a block comment.
+    pub block_comments: Vec<(String, String)>,
+    /// Whether or not block comments nest.
+    pub nested: bool,
+}
+
+impl<Stream: CharStream> StripComments<Stream> {
+    /// Create a [StripComments] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<Stream>) -> Self {
+        Self {
+            iter,
+            lookahead: VecDeque::new(),
+            position: Position::default(),
+            line_comments: vec![],
+            block_comments: vec![],
+            nested: false,
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Remove line comments starting with `marker`, like `//` or `#`.
+    ///
+    /// Panics if `marker` is empty, as it would match everywhere without consuming anything.
+    pub fn line_comment(mut self, marker: &str) -> Self {
+        assert!(!marker.is_empty(), "line comment marker must not be empty");
+        self.line_comments.push(marker.to_string());
+        self
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Remove block comments between `open` and `close`, like `/*` and `*/`.
+    ///
+    /// Panics if `open` or `close` is empty.
+    pub fn block_comment(mut self, open: &str, close: &str) -> Self {
+        assert!(
+            !open.is_empty() && !close.is_empty(),
+            "block comment markers must not be empty"
+        );
+        self.block_comments
+            .push((open.to_string(), close.to_string()));
+        self
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Let block comments nest, so every `open` inside a block comment needs its own `close`.
+    pub fn nested(mut self, nested: bool) -> Self {
+        self.nested = nested;
+        self
+    }
+
+    /// Whether the upcoming characters are `marker`.
+    fn starts_with(&mut self, marker: &str) -> bool {
+        for (n, expected) in marker.chars().enumerate() {
+            if self.lookahead.len() <= n {
+                match self.iter.next() {
+                    Some(item) => self.lookahead.push_back(item),
+                    None => return false,
+                }
+            }
+
+            if !matches!(self.lookahead[n], Ok(character) if character == expected) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Take the next item, moving the position past it.
+    fn advance(&mut self) -> Option<CharacterStreamResult> {
+        let item = self.lookahead.pop_front().or_else(|| self.iter.next())?;
+        if let Ok(character) = item {
+            self.position.advance(character, 1);
+        }
+
+        Some(item)
+    }
+
+    /// Skip `amount` characters that are known to be in `lookahead`.
+    fn skip(&mut self, amount: usize) {
+        for _ in 0..amount {
+            self.advance();
+        }
+    }
+
+    /// Skip a block comment, starting with its `open` marker.
+    fn skip_block(&mut self, open: &str, close: &str) -> Result<(), CharacterError> {
+        let start = self.position;
+        self.skip(open.chars().count());
+        let mut depth = 1;
+
+        while depth > 0 {
+            if self.starts_with(close) {
+                self.skip(close.chars().count());
+                depth -= 1;
+            } else if self.nested && self.starts_with(open) {
+                self.skip(open.chars().count());
+                depth += 1;
+            } else {
+                match self.advance() {
+                    Some(Ok(_)) => {}
+                    Some(Err(error)) => return Err(error),
+                    None => {
+                        return Err(CharacterError::Unterminated {
+                            what: "block comment",
+                            start: Some(start),
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<Stream: CharStream> Iterator for StripComments<Stream> {
+    type Item = Result<(char, Position), CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line_comments = std::mem::take(&mut self.line_comments);
+            let line_comment = line_comments.iter().find(|marker| self.starts_with(marker));
+            let skipped = line_comment.map(|marker| marker.chars().count());
+            self.line_comments = line_comments;
+
+            if let Some(skipped) = skipped {
+                self.skip(skipped);
+                while !self.starts_with("\n") {
+                    if let Err(error) = self.advance()? {
+                        return Some(Err(error));
+                    }
+                }
+                continue;
+            }
+
+            let block_comments = std::mem::take(&mut self.block_comments);
+            let block_comment = block_comments
+                .iter()
+                .find(|(open, _)| self.starts_with(open))
+                .cloned();
+            self.block_comments = block_comments;
+
+            if let Some((open, close)) = block_comment {
+                if let Err(error) = self.skip_block(&open, &close) {
+                    return Some(Err(error));
+                }
+                continue;
+            }
+
+            let position = self.position;
+            return Some(self.advance()?.map(|character| (character, position)));
+        }
+    }
+}
+
+/// Iterator over at most a set amount of characters borrowed from a [CharacterStream].
+///
+/// No bytes past the last character are consumed from the stream, so it can be used directly
+/// afterwards, raw bytes included through [read_bytes](CharacterStream::read_bytes).
+///
+/// The underlying reader itself is read in blocks and is usually past the last character, so never
+/// read from it directly. To continue without the stream, use [CharacterStream::into_reader], or
+/// [CharacterStream::into_inner] which also returns the bytes that were read ahead.
+///
+/// Created by [CharacterStream::take_chars].
+pub struct TakeChars<'a, Reader: Read> {
+    /// The stream the characters are read from.
+    pub(crate) stream: &'a mut CharacterStream<Reader>,
+    /// The amount of characters that may still be read.
+    pub(crate) remaining: usize,
+}
+
+impl<'a, Reader: Read> TakeChars<'a, Reader> {
+    /// Create a [TakeChars] reading at most `amount` characters from `stream`.
+    pub fn new(stream: &'a mut CharacterStream<Reader>, amount: usize) -> Self {
+        Self {
+            stream,
+            remaining: amount,
+        }
+    }
+}
+
+impl<Reader: Read> Iterator for TakeChars<'_, Reader> {
+    type Item = CharacterStreamResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        match self.stream.read_char() {
+            Err(CharacterError::NoBytesRead) => None,
+            Ok(character) => {
+                self.remaining -= 1;
+                Some(Ok(character))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+/// Iterator over chunks of valid text from a [CharacterStream].
+///
+/// Each chunk holds as much text as the stream's internal buffer does, invalid byte sequences are
+/// returned as errors between the chunks. In lossy mode, they are replaced within the chunks.
+///
+/// Created by [CharacterStream::chunks].
+pub struct Chunks<'a, Reader: Read> {
+    /// The stream the chunks are read from.
+    pub(crate) stream: &'a mut CharacterStream<Reader>,
+}
+
+impl<'a, Reader: Read> Chunks<'a, Reader> {
+    /// Create a [Chunks] reading from `stream`.
+    pub fn new(stream: &'a mut CharacterStream<Reader>) -> Self {
+        Self { stream }
+    }
+}
+
+impl<Reader: Read> Iterator for Chunks<'_, Reader> {
+    type Item = Result<String, CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = String::new();
+        self.stream.read_valid_chunk(&mut chunk);
+
+        if chunk.is_empty() {
+            match self.stream.read_char() {
+                Ok(character) => chunk.push(character),
+                Err(CharacterError::NoBytesRead) => return None,
+                Err(error) => return Some(Err(error)),
+            }
+
+            self.stream.read_valid_chunk(&mut chunk);
+        }
+
+        Some(Ok(chunk))
+    }
+}
+
+/// Where a character was found in a stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// The byte offsets of the character.
+    pub bytes: Range<usize>,
+    /// The index of the character, counted in characters.
+    pub index: usize,
+    /// The line and column of the character.
+    pub position: Position,
+}
+
+/// Iterator over the characters of a [CharacterStream] paired with their [Span].
+///
+/// Created by [CharacterIterator::spanned].
+pub struct Spanned<Reader: Read> {
+    /// The iterator the characters are read from.
+    pub(crate) iter: CharacterIterator<CharacterStream<Reader>>,
+    /// The position of the next character.
+    pub(crate) position: Position,
+    /// The distance between tab stops, in columns.
+    pub tab_width: usize,
+}
+
+impl<Reader: Read> Spanned<Reader> {
+    /// Create a [Spanned] from a [CharacterIterator].
+    pub fn new(iter: CharacterIterator<CharacterStream<Reader>>) -> Self {
+        Self {
+            iter,
+            position: Position::default(),
+            tab_width: 1,
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Place tab stops every `tab_width` columns. A `tab_width` of 1 counts a tab as a single column.
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+}
+
+impl<Reader: Read> Iterator for Spanned<Reader> {
+    type Item = Result<(char, Span), CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let character = match self.iter.next()? {
+            Ok(character) => character,
+            Err(error) => return Some(Err(error)),
+        };
+
+        let span = Span {
+            bytes: self.iter.stream.last_char_span().unwrap_or_default(),
+            index: self.iter.chars_read() - 1,
+            position: self.position,
+        };
+        self.position.advance(character, self.tab_width);
+
+        Some(Ok((character, span)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AsCharacterStream, ToCharacterIterator, ToCharacterStream};
+
+    #[test]
+    fn split_test() {
+        let fields: Vec<String> = "a,b,c"
+            .to_character_iterator()
+            .split(',')
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(fields, ["a", "b", "c"]);
+
+        let fields: Vec<String> = "a,b,"
+            .to_character_iterator()
+            .split(',')
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(fields, ["a", "b", ""]);
+
+        let mut split = b"a,\x80b,c".to_character_iterator().split(',');
+        assert_eq!(split.next().unwrap().unwrap(), "a");
+        assert!(split.next().unwrap().is_err());
+        assert!(split.next().is_none());
+    }
+
+    #[test]
+    fn split_str_test() {
+        let fields: Vec<_> = "a::b:c::::"
+            .to_character_stream()
+            .into_iter()
+            .split_str("::")
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(fields, ["a", "b:c", "", ""]);
+
+        let fields: Vec<_> = "abc"
+            .to_character_stream()
+            .into_iter()
+            .split_str("")
+            .collect();
+        assert_eq!(fields.len(), 1);
+    }
+
+    #[test]
+    fn raw_bytes_test() {
+        let chars: Vec<(char, Vec<u8>)> = b"a\xF0\x9F\x92\xBB\x80\xFF"
+            .to_character_iterator_lossy()
+            .with_raw_bytes()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            chars,
+            [
+                ('a', vec![b'a']),
+                ('\u{1F4BB}', vec![0xF0, 0x9F, 0x92, 0xBB]),
+                ('\u{FFFD}', vec![0x80]),
+                ('\u{FFFD}', vec![0xFF]),
+            ]
+        );
+    }
+
+    #[test]
+    fn context_test() {
+        let error = b"\x80"
+            .to_character_iterator()
+            .context("parsing header")
+            .next()
+            .unwrap()
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .ends_with("parsing header: Unexpected continuation byte 0x80."));
+        assert_eq!(error.bytes(), Some(&[0x80][..]));
+        assert!(matches!(
+            &error,
+            CharacterError::Context { label: "parsing header", source }
+                if matches!(**source, CharacterError::UnexpectedContinuation(0x80))
+        ));
+
+        let error = b"\xE0\x80\x80"
+            .to_character_iterator()
+            .context("parsing body")
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(error.to_string().starts_with("parsing body: "));
+        assert!(matches!(
+            &error,
+            CharacterError::Context { source, .. } if matches!(**source, CharacterError::Utf8 { .. })
+        ));
+    }
+
+    #[test]
+    fn collapse_whitespace_test() {
+        let collapsed: String = "a \t\n b"
+            .to_character_iterator()
+            .collapse_whitespace()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(collapsed, "a b");
+
+        let collapsed: String = "\n\n a b \r\n"
+            .to_character_iterator()
+            .collapse_whitespace()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(collapsed, " a b ");
+    }
+
+    #[test]
+    fn take_chars_test() {
+        let mut character_stream = "a\u{20AC}bc".to_character_stream();
+        let taken: String = character_stream
+            .take_chars(2)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(taken, "a\u{20AC}");
+        assert_eq!(character_stream.read_byte().unwrap(), b'b');
+
+        let mut character_stream = "ab\u{20AC}cdef"
+            .to_character_stream()
+            .with_buffer_capacity(4);
+        assert_eq!(character_stream.take_chars(3).count(), 3);
+        let (mut reader, mut remainder) = character_stream.into_inner();
+        reader.read_to_end(&mut remainder).unwrap();
+        assert_eq!(remainder, b"cdef");
+
+        let mut character_stream = "ab\u{20AC}cdefgh"
+            .to_character_stream()
+            .with_buffer_capacity(4);
+        assert_eq!(character_stream.take_chars(3).count(), 3);
+        assert!(character_stream.get_ref().position() > 5);
+        assert_eq!(character_stream.read_bytes(2).unwrap(), b"cd");
+        let mut remainder = Vec::new();
+        character_stream
+            .into_reader()
+            .read_to_end(&mut remainder)
+            .unwrap();
+        assert_eq!(remainder, b"efgh");
+    }
+
+    #[test]
+    fn spanned_test() {
+        let spans: Vec<(char, Span)> = "a\n\u{1F4BB}b"
+            .to_character_iterator()
+            .spanned()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let span = |bytes, index, line, column| Span {
+            bytes,
+            index,
+            position: Position { line, column },
+        };
+
+        assert_eq!(
+            spans,
+            [
+                ('a', span(0..1, 0, 1, 1)),
+                ('\n', span(1..2, 1, 1, 2)),
+                ('\u{1F4BB}', span(2..6, 2, 2, 1)),
+                ('b', span(6..7, 3, 2, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_newlines_test() {
+        let normalize = |preserve_lone_cr| -> String {
+            "a\r\nb\rc\n\r"
+                .to_character_iterator()
+                .normalize_newlines()
+                .preserve_lone_cr(preserve_lone_cr)
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(normalize(false), "a\nb\nc\n\n");
+        assert_eq!(normalize(true), "a\nb\rc\n\r");
+
+        let normalized = "a\r\nb\rc".to_character_iterator().normalize_newlines();
+        let mut character_stream = crate::PositionedCharacterStream::new(normalized);
+        let read: String = std::iter::from_fn(|| character_stream.read_char().ok()).collect();
+        assert_eq!(read, "a\nb\nc");
+        assert_eq!(character_stream.position(), Position { line: 3, column: 2 });
+
+        let normalized = "\r\nx".to_character_iterator().normalize_newlines();
+        let mut character_stream = crate::FixedPeekableCharacterStream::<_, 2>::new(normalized);
+        assert_eq!(
+            character_stream.peek_nth(1).unwrap().as_ref().unwrap(),
+            &'x'
+        );
+        assert_eq!(character_stream.read_char().unwrap(), '\n');
+    }
+
+    #[test]
+    fn chunks_test() {
+        let text = "valid \u{1F4BB}".repeat(2000);
+        let mut character_stream = text.as_character_stream();
+        let chunks: Vec<String> = character_stream.chunks().map(Result::unwrap).collect();
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), text);
+
+        let mut character_stream = b"ab\xFFcd".as_character_stream();
+        let mut chunks = character_stream.chunks();
+        assert_eq!(chunks.next().unwrap().unwrap(), "ab");
+        assert!(chunks.next().unwrap().is_err());
+        assert_eq!(chunks.next().unwrap().unwrap(), "cd");
+        assert!(chunks.next().is_none());
+    }
+
+    #[test]
+    fn indented_test() {
+        use IndentEvent::*;
+
+        let events: Vec<_> = "a\n  b\n\n\tc\n  d\ne"
+            .to_character_stream()
+            .into_iter()
+            .indented()
+            .tab_width(4)
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(
+            events,
+            [
+                Char('a'),
+                Newline,
+                Indent,
+                Char('b'),
+                Newline,
+                Newline,
+                Indent,
+                Char('c'),
+                Newline,
+                Dedent,
+                Char('d'),
+                Newline,
+                Dedent,
+                Char('e'),
+            ]
+        );
+
+        let events: Vec<_> = "x\n y"
+            .to_character_stream()
+            .into_iter()
+            .indented()
+            .collect();
+        assert!(matches!(events.last(), Some(Ok(Dedent))));
+
+        let events: Vec<_> = "\ta"
+            .to_character_stream()
+            .into_iter()
+            .indented()
+            .tab_width(0)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events, [Indent, Char('a'), Dedent]);
+
+        let events: Vec<_> = "a\r\n  b\r  c\r\n\r\nd"
+            .to_character_stream()
+            .into_iter()
+            .indented()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            events,
+            [
+                Char('a'),
+                Newline,
+                Indent,
+                Char('b'),
+                Newline,
+                Char('c'),
+                Newline,
+                Newline,
+                Dedent,
+                Char('d'),
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_test() {
+        let lines: Vec<_> = "a\r\nb\n\nc\rd\n"
+            .to_character_stream()
+            .into_iter()
+            .lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, ["a", "b", "", "c", "d"]);
+
+        let mut lines = b"ok\nb\xFFd\nnever"
+            .to_character_stream()
+            .into_iter()
+            .lines();
+        assert_eq!(lines.next().unwrap().unwrap(), "ok");
+        assert!(matches!(
+            lines.next(),
+            Some(Err(CharacterError::Line { line: 2, .. }))
+        ));
+        assert!(lines.next().is_none());
+
+        let lines: Result<Vec<_>, _> = b"a\n\xFF"
+            .to_character_stream()
+            .into_iter()
+            .lines()
+            .collect();
+        let error = lines.unwrap_err();
+        assert_eq!(error.bytes(), Some(&b"\xFF"[..]));
+        assert!(error.to_string().starts_with("line 2: "));
+
+        let lines: Vec<_> = b"b\xFFd"
+            .to_character_stream_lossy()
+            .into_iter()
+            .lines()
+            .collect();
+        assert_eq!(lines[0].as_ref().unwrap(), "b\u{FFFD}d");
+    }
+
+    #[test]
+    fn words_test() {
+        let words: Vec<_> = " \tthe  quick\u{3000}brown\nfox "
+            .to_character_stream()
+            .into_iter()
+            .words()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(words, ["the", "quick", "brown", "fox"]);
+
+        assert!("  "
+            .to_character_stream()
+            .into_iter()
+            .words()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn strip_comments_test() {
+        let text = "a // x\n/* y\n /* z */ */b # w\nc";
+        let stripped: Vec<_> = text
+            .to_character_stream()
+            .into_iter()
+            .strip_comments()
+            .line_comment("//")
+            .line_comment("#")
+            .block_comment("/*", "*/")
+            .nested(true)
+            .map(Result::unwrap)
+            .collect();
+
+        let text: String = stripped.iter().map(|(character, _)| character).collect();
+        assert_eq!(text, "a \nb \nc");
+        assert_eq!(
+            stripped[3].1,
+            Position {
+                line: 3,
+                column: 12
+            }
+        );
+        assert_eq!(stripped[6].1, Position { line: 4, column: 1 });
+
+        let mut unterminated = "/* /* */"
+            .to_character_stream()
+            .into_iter()
+            .strip_comments();
+        unterminated = unterminated.block_comment("/*", "*/").nested(true);
+        assert!(matches!(
+            unterminated.next(),
+            Some(Err(CharacterError::Unterminated {
+                start: Some(Position { line: 1, column: 1 }),
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be empty")]
+    fn strip_comments_empty_marker_test() {
+        let _ = "a"
+            .to_character_iterator()
+            .strip_comments()
+            .line_comment("");
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be empty")]
+    fn strip_comments_empty_block_test() {
+        let _ = "a"
+            .to_character_iterator()
+            .strip_comments()
+            .block_comment("", "*/");
+    }
+}
@@ -0,0 +1,653 @@
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    io,
+    marker::PhantomData,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use crate::{
+    CharacterError, CharacterStreamResult, Decoded, LineEnding, LineSplitter, LineStep, MultiPeek,
+    Peek, Utf8Decoder, DEFAULT_BUFFER_CAPACITY,
+};
+
+/// A source of bytes that can be read asynchronously.
+///
+/// Implemented for every `tokio::io::AsyncRead` with the `tokio` feature, and for
+/// `futures_io::AsyncRead` through [FuturesReader] with the `futures-io` feature.
+///
+/// `async-std` and `smol` readers implement `futures_io::AsyncRead`, so the `async-std` and `smol`
+/// features only enable `futures-io`. Other runtimes can implement this trait directly.
+pub trait AsyncByteSource {
+    /// Attempts to read bytes into `buf`, returning how many were read.
+    ///
+    /// Returns `Ok(0)` once the source has ended.
+    fn poll_read_bytes(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>>;
+}
+
+#[cfg(feature = "tokio")]
+impl<T: tokio::io::AsyncRead + ?Sized> AsyncByteSource for T {
+    fn poll_read_bytes(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut buf = tokio::io::ReadBuf::new(buf);
+        ready!(self.poll_read(cx, &mut buf))?;
+
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+}
+
+/// Wrapper struct that reads bytes from a [futures_io::AsyncRead], without depending on tokio.
+#[cfg(feature = "futures-io")]
+#[derive(Debug)]
+pub struct FuturesReader<Reader: futures_io::AsyncRead + Unpin>(pub Reader);
+
+#[cfg(feature = "futures-io")]
+impl<Reader: futures_io::AsyncRead + Unpin> AsyncByteSource for FuturesReader<Reader> {
+    fn poll_read_bytes(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+
+/// Wrapper struct for any asynchronous stream, decoding its bytes as UTF-8.
+pub struct AsyncCharacterStream<Reader: AsyncByteSource + Unpin> {
+    /// The stream from which the incoming bytes are from.
+    pub stream: Reader,
+    /// Whether or not we should care whether invalid bytes are detected.
+    ///
+    /// If `true`, then invalid byte sequences will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+    /// Report overlong sequences, encoded surrogates and code points above U+10FFFF with their own
+    /// [error](CharacterError) variants.
+    pub strict: bool,
+    /// Bytes read from the stream ahead of decoding.
+    ///
+    /// They are kept between polls, so a pending read does not lose them.
+    buffer: Vec<u8>,
+    /// The position of the next undecoded byte in `buffer`.
+    buffer_start: usize,
+    /// The end of the bytes read into `buffer`.
+    buffer_end: usize,
+}
+
+impl<Reader: AsyncByteSource + Unpin> AsyncCharacterStream<Reader> {
+    /// Create an [AsyncCharacterStream] from an asynchronous stream.
+    pub fn new(stream: Reader, is_lossy: bool) -> Self {
+        Self {
+            stream,
+            is_lossy,
+            strict: false,
+            buffer: vec![0; DEFAULT_BUFFER_CAPACITY],
+            buffer_start: 0,
+            buffer_end: 0,
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// See [CharacterStream::strict](crate::CharacterStream::strict).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Convert into an [AsyncLines] that reads the stream line by line.
+    pub fn lines(self) -> AsyncLines<Reader> {
+        AsyncLines {
+            stream: self,
+            line: String::new(),
+            splitter: LineSplitter::default(),
+        }
+    }
+
+    /// Convert into an [AsyncPeekableCharacterStream] that can peek a single character.
+    pub fn peeky(self) -> AsyncPeekableCharacterStream<Reader, Peek> {
+        AsyncPeekableCharacterStream::from(self)
+    }
+
+    /// Convert into an [AsyncPeekableCharacterStream] that can peek any amount of characters.
+    pub fn peeky_multi(self) -> AsyncPeekableCharacterStream<Reader, MultiPeek> {
+        AsyncPeekableCharacterStream::from(self)
+    }
+
+    /// Attempts to read a character from the stream.
+    ///
+    /// If `is_lossy` is set to `true`, then invalid byte sequences will be a U+FFFD.
+    ///
+    /// If `is_lossy` is set to `false`, then invalid byte sequences will be returned in addition to a parse error.
+    ///
+    /// This is cancel safe: if the future is dropped in the middle of a multi-byte character, the
+    /// bytes read so far are kept and the next call resumes the same character.
+    pub async fn read_char(&mut self) -> CharacterStreamResult {
+        poll_fn(|cx| self.poll_read_char(cx)).await
+    }
+
+    /// Attempts to read a character from the stream, treating the end of the stream as `Ok(None)`.
+    pub async fn read_char_or_eof(&mut self) -> Result<Option<char>, CharacterError> {
+        match self.read_char().await {
+            Ok(character) => Ok(Some(character)),
+            Err(CharacterError::NoBytesRead) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Polls for the next character, see [AsyncCharacterStream::read_char].
+    pub fn poll_read_char(&mut self, cx: &mut Context<'_>) -> Poll<CharacterStreamResult> {
+        let decoder = Utf8Decoder::new(self.is_lossy).strict(self.strict);
+
+        loop {
+            let available = &self.buffer[self.buffer_start..self.buffer_end];
+            let (result, used) = match decoder.decode(available) {
+                Decoded::Char(character, used) => (Ok(character), used),
+                Decoded::Invalid(error, used) => (Err(error), used),
+                Decoded::Incomplete => {
+                    // Move the start of an incomplete character to the front, to read the rest of
+                    // it behind it.
+                    self.buffer
+                        .copy_within(self.buffer_start..self.buffer_end, 0);
+                    self.buffer_end -= self.buffer_start;
+                    self.buffer_start = 0;
+
+                    let end = self.buffer_end;
+                    let buf = &mut self.buffer[end..];
+                    match ready!(Pin::new(&mut self.stream).poll_read_bytes(cx, buf)) {
+                        Ok(0) if end == 0 => return Poll::Ready(Err(CharacterError::NoBytesRead)),
+                        Ok(0) => {
+                            self.buffer_end = 0;
+                            return Poll::Ready(decoder.decode_truncated(&self.buffer[..end]));
+                        }
+                        Ok(read) => self.buffer_end += read,
+                        Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                        Err(error) => {
+                            self.buffer_end = 0;
+                            return Poll::Ready(Err(CharacterError::IoError {
+                                bytes: self.buffer[..end].to_vec(),
+                                error,
+                            }));
+                        }
+                    }
+                    continue;
+                }
+            };
+            self.buffer_start += used;
+
+            return Poll::Ready(result);
+        }
+    }
+}
+
+/// Yields characters until the end of the stream, like [CharacterIterator](crate::CharacterIterator).
+#[cfg(feature = "futures-core")]
+impl<Reader: AsyncByteSource + Unpin> futures_core::Stream for AsyncCharacterStream<Reader> {
+    type Item = CharacterStreamResult;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match ready!(self.get_mut().poll_read_char(cx)) {
+            Ok(character) => Poll::Ready(Some(Ok(character))),
+            Err(CharacterError::NoBytesRead) => Poll::Ready(None),
+            Err(CharacterError::IoError { error, .. })
+                if error.kind() == io::ErrorKind::UnexpectedEof =>
+            {
+                Poll::Ready(None)
+            }
+            Err(error) => Poll::Ready(Some(Err(error))),
+        }
+    }
+}
+
+impl<Reader: AsyncByteSource + Unpin + std::fmt::Debug> std::fmt::Debug
+    for AsyncCharacterStream<Reader>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncCharacterStream")
+            .field("stream", &self.stream)
+            .field("is_lossy", &self.is_lossy)
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
+/// Asynchronous counterpart of [PeekableCharacterStream](crate::PeekableCharacterStream).
+pub struct AsyncPeekableCharacterStream<Reader: AsyncByteSource + Unpin, PI> {
+    pub stream: AsyncCharacterStream<Reader>,
+    pub buffer: VecDeque<CharacterStreamResult>,
+    pub position: usize,
+    /// Maximum amount of characters the peek buffer may hold.
+    ///
+    /// If `None`, then the buffer may grow without bound.
+    pub max_peek_buffer: Option<usize>,
+    /// Holds the error returned once the peek buffer limit is exceeded.
+    overflow: Option<CharacterStreamResult>,
+    _phantom: PhantomData<PI>,
+}
+
+impl<Reader: AsyncByteSource + Unpin, PI> AsyncPeekableCharacterStream<Reader, PI> {
+    pub fn new(stream: Reader, is_lossy: bool) -> Self {
+        Self::from(AsyncCharacterStream::new(stream, is_lossy))
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Limit the peek buffer to `max` characters.
+    pub fn with_max_peek(mut self, max: usize) -> Self {
+        self.max_peek_buffer = Some(max);
+        self
+    }
+
+    #[inline]
+    async fn _read_char(&mut self) -> CharacterStreamResult {
+        match self.buffer.pop_front() {
+            Some(result) => result,
+            None => self.stream.read_char().await,
+        }
+    }
+}
+
+impl<Reader: AsyncByteSource + Unpin, PI> From<AsyncCharacterStream<Reader>>
+    for AsyncPeekableCharacterStream<Reader, PI>
+{
+    fn from(stream: AsyncCharacterStream<Reader>) -> Self {
+        Self {
+            stream,
+            buffer: VecDeque::new(),
+            position: 0,
+            max_peek_buffer: None,
+            overflow: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Reader: AsyncByteSource + Unpin> AsyncPeekableCharacterStream<Reader, Peek> {
+    /// Peek the next character, without consuming it.
+    pub async fn peek(&mut self) -> Option<&CharacterStreamResult> {
+        if self.buffer.is_empty() {
+            let character_result = self.stream.read_char().await;
+            self.buffer.push_back(character_result);
+        }
+
+        self.buffer.front()
+    }
+
+    /// Attempts to read a character from the stream, consuming a peeked character first.
+    pub async fn read_char(&mut self) -> CharacterStreamResult {
+        self._read_char().await
+    }
+
+    /// Reads a line from the stream, ended by `\n`, `\r\n` or a lone `\r`.
+    ///
+    /// The line ending is not included in the returned string.
+    ///
+    /// Returns `Ok(None)` if the stream has ended.
+    ///
+    /// This is not cancel safe, the characters of a partially read line are lost if the future is
+    /// dropped. Use [AsyncLines] instead if that matters.
+    pub async fn read_line(&mut self) -> Result<Option<String>, CharacterError> {
+        let mut line = String::new();
+        let mut splitter = LineSplitter::default();
+
+        loop {
+            match self.read_char().await {
+                Ok(character) => match splitter.feed(character) {
+                    LineStep::Char(character) => line.push(character),
+                    LineStep::End(ending) => {
+                        if ending == LineEnding::Cr {
+                            if let Some(Ok('\n')) = self.peek().await {
+                                self.buffer.clear();
+                            }
+                        }
+
+                        return Ok(Some(line));
+                    }
+                    LineStep::Skip => {}
+                },
+                Err(CharacterError::NoBytesRead) if line.is_empty() => return Ok(None),
+                Err(CharacterError::NoBytesRead) => return Ok(Some(line)),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl<Reader: AsyncByteSource + Unpin> AsyncPeekableCharacterStream<Reader, MultiPeek> {
+    /// Peek the character after the previously peeked one, without consuming it.
+    ///
+    /// Returns `None` once the end of the stream is peeked.
+    ///
+    /// If peeking would grow the buffer past [max_peek_buffer](AsyncPeekableCharacterStream::max_peek_buffer),
+    /// [CharacterError::PeekLimitExceeded] is returned instead.
+    pub async fn peek(&mut self) -> Option<&CharacterStreamResult> {
+        if self.position >= self.buffer.len() {
+            if let Some(max) = self.max_peek_buffer {
+                if self.buffer.len() >= max {
+                    self.overflow = Some(Err(CharacterError::PeekLimitExceeded(max)));
+                    return self.overflow.as_ref();
+                }
+            }
+
+            match self.stream.read_char().await {
+                Err(CharacterError::NoBytesRead) => return None,
+                result => self.buffer.push_back(result),
+            }
+        }
+
+        self.position += 1;
+        self.buffer.get(self.position - 1)
+    }
+
+    /// Start peeking from the next unread character again.
+    pub fn reset_peek(&mut self) {
+        self.position = 0;
+    }
+
+    /// Attempts to read a character from the stream, consuming peeked characters first.
+    pub async fn read_char(&mut self) -> CharacterStreamResult {
+        self.reset_peek();
+        self._read_char().await
+    }
+}
+
+impl<Reader: AsyncByteSource + Unpin + std::fmt::Debug, PI> std::fmt::Debug
+    for AsyncPeekableCharacterStream<Reader, PI>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncPeekableCharacterStream")
+            .field("stream", &self.stream)
+            .field("buffer", &self.buffer)
+            .field("position", &self.position)
+            .field("max_peek_buffer", &self.max_peek_buffer)
+            .finish()
+    }
+}
+
+/// Reads an [AsyncCharacterStream] line by line, see [AsyncCharacterStream::lines].
+pub struct AsyncLines<Reader: AsyncByteSource + Unpin> {
+    pub stream: AsyncCharacterStream<Reader>,
+    /// The line read so far.
+    line: String,
+    /// Where the current line ends, a `\n` following a `\r` belongs to the previous line.
+    splitter: LineSplitter,
+}
+
+impl<Reader: AsyncByteSource + Unpin> AsyncLines<Reader> {
+    /// Reads the next line, without its line ending.
+    ///
+    /// Returns `Ok(None)` if the stream has ended.
+    ///
+    /// This is cancel safe, a partially read line is kept until the next call.
+    pub async fn next_line(&mut self) -> Result<Option<String>, CharacterError> {
+        poll_fn(|cx| self.poll_next_line(cx)).await
+    }
+
+    /// Polls for the next line, see [AsyncLines::next_line].
+    pub fn poll_next_line(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<String>, CharacterError>> {
+        loop {
+            let character = match ready!(self.stream.poll_read_char(cx)) {
+                Ok(character) => character,
+                Err(CharacterError::NoBytesRead) if self.line.is_empty() => {
+                    return Poll::Ready(Ok(None))
+                }
+                Err(CharacterError::NoBytesRead) => {
+                    return Poll::Ready(Ok(Some(std::mem::take(&mut self.line))))
+                }
+                Err(error) => return Poll::Ready(Err(error)),
+            };
+
+            match self.splitter.feed(character) {
+                LineStep::Char(character) => self.line.push(character),
+                LineStep::End(_) => return Poll::Ready(Ok(Some(std::mem::take(&mut self.line)))),
+                LineStep::Skip => {}
+            }
+        }
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl<Reader: AsyncByteSource + Unpin> futures_core::Stream for AsyncLines<Reader> {
+    type Item = Result<String, CharacterError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_line(cx).map(Result::transpose)
+    }
+}
+
+impl<Reader: AsyncByteSource + Unpin + std::fmt::Debug> std::fmt::Debug for AsyncLines<Reader> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncLines")
+            .field("stream", &self.stream)
+            .field("line", &self.line)
+            .field("splitter", &self.splitter)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_read_char_test() {
+        let bytes: &[u8] = b"a\xE2\x82\xAC\xF0\x9F\x92\xBB\xFFb";
+        let mut character_stream = AsyncCharacterStream::new(bytes, true);
+        let mut characters = String::new();
+
+        while let Some(character) = character_stream.read_char_or_eof().await.unwrap() {
+            characters.push(character);
+        }
+
+        assert_eq!(characters, "a\u{20AC}\u{1F4BB}\u{FFFD}b");
+
+        let bytes: &[u8] = b"\xE2\x82";
+        let mut character_stream = AsyncCharacterStream::new(bytes, false);
+        assert!(matches!(
+            character_stream.read_char().await,
+            Err(CharacterError::TruncatedSequence { bytes, expected: 3, got: 2 }) if bytes == [0xE2, 0x82]
+        ));
+    }
+
+    #[cfg(feature = "futures-io")]
+    #[test]
+    fn futures_read_char_test() {
+        let reader = futures::io::Cursor::new("h\u{E9}llo".as_bytes());
+        let mut character_stream = AsyncCharacterStream::new(FuturesReader(reader), false);
+        let mut characters = String::new();
+
+        futures::executor::block_on(async {
+            while let Some(character) = character_stream.read_char_or_eof().await.unwrap() {
+                characters.push(character);
+            }
+        });
+
+        assert_eq!(characters, "h\u{E9}llo");
+    }
+
+    #[cfg(all(feature = "futures-core", feature = "futures-io"))]
+    #[test]
+    fn stream_test() {
+        use futures::StreamExt;
+
+        let reader = futures::io::Cursor::new("ab\u{1F4BB} cd".as_bytes());
+        let character_stream = AsyncCharacterStream::new(FuturesReader(reader), false);
+
+        let characters: String = futures::executor::block_on(
+            character_stream
+                .map(Result::unwrap)
+                .take_while(|character| std::future::ready(*character != ' '))
+                .collect(),
+        );
+
+        assert_eq!(characters, "ab\u{1F4BB}");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_peek_test() {
+        let bytes: &[u8] = b"a\xC3\xA9c";
+        let mut character_stream = AsyncCharacterStream::new(bytes, false).peeky();
+        assert_eq!(
+            character_stream.peek().await.unwrap().as_ref().unwrap(),
+            &'a'
+        );
+        assert_eq!(character_stream.read_char().await.unwrap(), 'a');
+        assert_eq!(character_stream.read_char().await.unwrap(), '\u{E9}');
+
+        let bytes: &[u8] = b"abc";
+        let mut character_stream = AsyncCharacterStream::new(bytes, false).peeky_multi();
+        assert_eq!(
+            character_stream.peek().await.unwrap().as_ref().unwrap(),
+            &'a'
+        );
+        assert_eq!(
+            character_stream.peek().await.unwrap().as_ref().unwrap(),
+            &'b'
+        );
+        assert_eq!(
+            character_stream.peek().await.unwrap().as_ref().unwrap(),
+            &'c'
+        );
+        assert!(character_stream.peek().await.is_none());
+        assert_eq!(character_stream.read_char().await.unwrap(), 'a');
+        assert_eq!(
+            character_stream.peek().await.unwrap().as_ref().unwrap(),
+            &'b'
+        );
+        assert_eq!(character_stream.read_char().await.unwrap(), 'b');
+        assert_eq!(character_stream.read_char().await.unwrap(), 'c');
+        assert!(matches!(
+            character_stream.read_char().await,
+            Err(CharacterError::NoBytesRead)
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_max_peek_test() {
+        let bytes: &[u8] = b"abc";
+        let mut character_stream = AsyncCharacterStream::new(bytes, false)
+            .peeky_multi()
+            .with_max_peek(2);
+
+        character_stream.peek().await;
+        character_stream.peek().await;
+        assert!(matches!(
+            character_stream.peek().await,
+            Some(Err(CharacterError::PeekLimitExceeded(2)))
+        ));
+        assert_eq!(character_stream.read_char().await.unwrap(), 'a');
+        assert_eq!(
+            character_stream.peek().await.unwrap().as_ref().unwrap(),
+            &'b'
+        );
+    }
+
+    #[test]
+    fn custom_byte_source_test() {
+        struct Bytes(std::vec::IntoIter<u8>);
+
+        impl AsyncByteSource for Bytes {
+            fn poll_read_bytes(
+                mut self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<io::Result<usize>> {
+                let mut read = 0;
+                for (slot, byte) in buf.iter_mut().zip(&mut self.0) {
+                    *slot = byte;
+                    read += 1;
+                }
+
+                Poll::Ready(Ok(read))
+            }
+        }
+
+        let source = Bytes("\u{1F4BB}!".as_bytes().to_vec().into_iter());
+        let mut character_stream = AsyncCharacterStream::new(source, false);
+        let mut characters = String::new();
+
+        futures::executor::block_on(async {
+            while let Some(character) = character_stream.read_char_or_eof().await.unwrap() {
+                characters.push(character);
+            }
+        });
+
+        assert_eq!(characters, "\u{1F4BB}!");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_lines_test() {
+        let text = "lf\ncrlf\r\ncr\rlast";
+
+        let mut character_stream = AsyncCharacterStream::new(text.as_bytes(), false).peeky();
+        let mut lines = vec![];
+        while let Some(line) = character_stream.read_line().await.unwrap() {
+            lines.push(line);
+        }
+        assert_eq!(lines, ["lf", "crlf", "cr", "last"]);
+
+        let mut character_stream = AsyncCharacterStream::new(text.as_bytes(), false).lines();
+        let mut lines = vec![];
+        while let Some(line) = character_stream.next_line().await.unwrap() {
+            lines.push(line);
+        }
+        assert_eq!(lines, ["lf", "crlf", "cr", "last"]);
+    }
+
+    #[test]
+    fn cancel_safety_test() {
+        use std::future::Future;
+
+        /// Yields `Pending` before every byte.
+        struct Slow(std::vec::IntoIter<u8>, bool);
+
+        impl AsyncByteSource for Slow {
+            fn poll_read_bytes(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<io::Result<usize>> {
+                self.1 = !self.1;
+                if self.1 {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                } else {
+                    let byte = self.0.next();
+                    buf[0] = byte.unwrap_or_default();
+                    Poll::Ready(Ok(byte.map_or(0, |_| 1)))
+                }
+            }
+        }
+
+        let source = Slow("\u{20AC}".as_bytes().to_vec().into_iter(), false);
+        let mut character_stream = AsyncCharacterStream::new(source, false);
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+        // Drop each future after it has read at most one byte.
+        for _ in 0..3 {
+            let future = std::pin::pin!(character_stream.read_char());
+            assert!(future.poll(&mut cx).is_pending());
+        }
+
+        assert_eq!(
+            futures::executor::block_on(character_stream.read_char()).unwrap(),
+            '\u{20AC}'
+        );
+    }
+}
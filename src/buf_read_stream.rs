@@ -0,0 +1,209 @@
+use std::io::{self, BufRead};
+
+use crate::{
+    CharStream, CharacterError, CharacterIterator, CharacterStreamResult, Decoded, MultiPeek, Peek,
+    PeekableCharacterStream, Utf8Decoder, INTERRUPTED_MAX,
+};
+
+/// Wrapper struct for any stream that implements [BufRead].
+///
+/// Unlike [CharacterStream](crate::CharacterStream), characters are decoded in place from the
+/// reader's buffer, only copying bytes when a character straddles the end of the buffer.
+pub struct BufReadCharacterStream<Reader: BufRead> {
+    /// The stream from which the incoming bytes are from.
+    pub stream: Reader,
+    /// Whether or not we should care whether invalid bytes are detected.
+    ///
+    /// If `true`, then invalid byte sequences will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+}
+
+impl<Reader: BufRead> BufReadCharacterStream<Reader> {
+    /// Create a [BufReadCharacterStream] from a stream.
+    ///
+    /// Set `is_lossy` to `true` if you don't want to handle invalid byte sequences.
+    pub fn new(stream: Reader, is_lossy: bool) -> Self {
+        Self { stream, is_lossy }
+    }
+
+    /// Kinda builder pattern.
+    pub fn lossy(mut self, is_lossy: bool) -> Self {
+        self.is_lossy = is_lossy;
+        self
+    }
+
+    /// Wrap `self` into a single-peek [PeekableCharacterStream].
+    pub fn peeky(self) -> PeekableCharacterStream<Reader, Peek, Self> {
+        PeekableCharacterStream::from_stream(self)
+    }
+
+    /// Wrap `self` into a multi-peek [PeekableCharacterStream].
+    pub fn peeky_multi(self) -> PeekableCharacterStream<Reader, MultiPeek, Self> {
+        PeekableCharacterStream::from_stream(self)
+    }
+
+    /// Return the underlying stream, undecoded bytes are left in its buffer.
+    pub fn into_inner(self) -> Reader {
+        self.stream
+    }
+
+    /// Decode a character that straddles the end of the reader's buffer, one byte at a time so
+    /// a byte that ends an invalid sequence early is left in the reader.
+    fn read_straddling(&mut self) -> CharacterStreamResult {
+        let decoder = Utf8Decoder::new(self.is_lossy);
+        let mut bytes = [0u8; 4];
+        let mut len = 0;
+
+        loop {
+            let buffer = match self.stream.fill_buf() {
+                Ok(buffer) => buffer,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    return Err(CharacterError::IoError {
+                        bytes: bytes[..len].to_vec(),
+                        error,
+                    })
+                }
+            };
+
+            let Some(&byte) = buffer.first() else {
+                return decoder.decode_truncated(&bytes[..len]);
+            };
+            bytes[len] = byte;
+
+            let (result, used) = match decoder.decode(&bytes[..=len]) {
+                Decoded::Char(character, used) => (Ok(character), used),
+                Decoded::Invalid(error, used) => (Err(error), used),
+                Decoded::Incomplete => {
+                    self.stream.consume(1);
+                    len += 1;
+                    continue;
+                }
+            };
+            if used > len {
+                self.stream.consume(1);
+            }
+
+            return result;
+        }
+    }
+}
+
+impl<Reader: BufRead> CharStream for BufReadCharacterStream<Reader> {
+    /// Attempts to read a character from the stream.
+    ///
+    /// If `is_lossy` is set to `true`, then invalid byte sequences will be a U+FFFD.
+    ///
+    /// If `is_lossy` is set to `false`, then invalid byte sequences will be returned in addition to a parse error.
+    fn read_char(&mut self) -> CharacterStreamResult {
+        let buffer = match self.stream.fill_buf() {
+            Ok([]) => return Err(CharacterError::NoBytesRead),
+            Ok(buffer) => buffer,
+            Err(error) => {
+                return Err(CharacterError::IoError {
+                    bytes: vec![],
+                    error,
+                })
+            }
+        };
+
+        let (result, used) = match Utf8Decoder::new(self.is_lossy).decode(buffer) {
+            Decoded::Char(character, used) => (Ok(character), used),
+            Decoded::Invalid(error, used) => (Err(error), used),
+            Decoded::Incomplete => return self.read_straddling(),
+        };
+        self.stream.consume(used);
+
+        result
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+}
+
+impl<Reader: BufRead + std::fmt::Debug> std::fmt::Debug for BufReadCharacterStream<Reader> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufReadCharacterStream")
+            .field("stream", &self.stream)
+            .field("is_lossy", &self.is_lossy)
+            .finish()
+    }
+}
+
+impl<Reader: BufRead> From<Reader> for BufReadCharacterStream<Reader> {
+    fn from(reader: Reader) -> Self {
+        Self::new(reader, false)
+    }
+}
+
+impl<Reader: BufRead> IntoIterator for BufReadCharacterStream<Reader> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::*;
+
+    #[test]
+    fn straddling_buffer_test() {
+        let reader = BufReader::with_capacity(2, Cursor::new("a\u{1F4BB}b\u{E9}".as_bytes()));
+        let read: String = BufReadCharacterStream::from(reader)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(read, "a\u{1F4BB}b\u{E9}");
+    }
+
+    #[test]
+    fn buf_read_peek_test() {
+        use crate::Peekable;
+
+        let reader = BufReader::with_capacity(3, Cursor::new("\u{1F4BB}!".as_bytes()));
+        let mut character_stream = BufReadCharacterStream::from(reader).peeky();
+
+        assert_eq!(
+            character_stream.peek().unwrap().as_ref().unwrap(),
+            &'\u{1F4BB}'
+        );
+        assert_eq!(character_stream.read_char().unwrap(), '\u{1F4BB}');
+        assert_eq!(character_stream.read_char().unwrap(), '!');
+        assert!(character_stream
+            .stream
+            .into_inner()
+            .fill_buf()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn invalid_prefix_test() {
+        for capacity in [1, 2, 16] {
+            let reader = BufReader::with_capacity(capacity, Cursor::new(b"\xE2AB\xF0\x9FC"));
+            let mut character_stream = BufReadCharacterStream::from(reader);
+
+            assert!(matches!(
+                character_stream.read_char(),
+                Err(CharacterError::InvalidSequence(bytes)) if bytes == [0xE2]
+            ));
+            assert_eq!(character_stream.read_char().unwrap(), 'A');
+            assert_eq!(character_stream.read_char().unwrap(), 'B');
+            assert!(matches!(
+                character_stream.read_char(),
+                Err(CharacterError::InvalidSequence(bytes)) if bytes == [0xF0, 0x9F]
+            ));
+            assert_eq!(character_stream.read_char().unwrap(), 'C');
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::{collections::VecDeque, io::Read};
+
+use crate::{
+    is_encoded_surrogate, read_bytes, remaining_byte_count, surrogate_code_point, CharacterError,
+    CharacterStreamResult, Decoder,
+};
+
+/// [Decoder] for CESU-8 and Java's Modified UTF-8.
+///
+/// Supplementary characters encoded as a pair of three byte surrogates are combined into a single
+/// character, and `0xC0 0x80` is decoded as U+0000.
+#[derive(Debug, Clone, Default)]
+pub struct Cesu8Decoder {
+    /// Whether or not we should care whether invalid bytes are detected.
+    ///
+    /// If `true`, then invalid byte sequences and lone surrogates will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+    /// Bytes that were read while looking for a low surrogate, but did not belong to the pair.
+    pending: VecDeque<u8>,
+}
+
+impl Cesu8Decoder {
+    /// Create a [Cesu8Decoder].
+    pub fn new(is_lossy: bool) -> Self {
+        Self {
+            is_lossy,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn read_byte(&mut self, reader: &mut impl Read) -> Result<u8, CharacterError> {
+        match self.pending.pop_front() {
+            Some(byte) => Ok(byte),
+            None => Ok(read_bytes(reader, 1)?[0]),
+        }
+    }
+
+    /// Read the bytes of the sequence started by `first_byte`.
+    fn read_sequence(
+        &mut self,
+        reader: &mut impl Read,
+        first_byte: u8,
+        remaining_count: usize,
+    ) -> Result<Vec<u8>, CharacterError> {
+        let mut bytes = vec![first_byte];
+
+        for _ in 0..remaining_count {
+            match self.read_byte(reader) {
+                Ok(byte) => bytes.push(byte),
+                Err(CharacterError::NoBytesRead) => return Err(CharacterError::truncated(bytes)),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Read the low surrogate that should follow a high surrogate.
+    ///
+    /// If the next bytes are not a low surrogate, they are kept for the next character.
+    fn read_low_surrogate(
+        &mut self,
+        reader: &mut impl Read,
+    ) -> Result<Option<u16>, CharacterError> {
+        let mut bytes = vec![];
+
+        while bytes.len() < 3 {
+            match self.read_byte(reader) {
+                Ok(byte) => bytes.push(byte),
+                Err(CharacterError::NoBytesRead) => break,
+                Err(error) => {
+                    self.pending.extend(bytes);
+                    return Err(error);
+                }
+            }
+
+            if bytes[0] != 0xED {
+                break;
+            }
+        }
+
+        if is_encoded_surrogate(&bytes) && bytes[1] >= 0xB0 {
+            Ok(Some(surrogate_code_point(&bytes)))
+        } else {
+            for byte in bytes.into_iter().rev() {
+                self.pending.push_front(byte);
+            }
+
+            Ok(None)
+        }
+    }
+
+    fn lone_surrogate(&self, bytes: Vec<u8>) -> CharacterStreamResult {
+        if self.is_lossy {
+            Ok('\u{FFFD}')
+        } else {
+            let code_point = surrogate_code_point(&bytes);
+            Err(CharacterError::Surrogate { bytes, code_point })
+        }
+    }
+}
+
+impl Decoder for Cesu8Decoder {
+    fn decode_next(&mut self, reader: &mut impl Read) -> CharacterStreamResult {
+        let first_byte = self.read_byte(reader)?;
+        let remaining_count = match remaining_byte_count(first_byte) {
+            Some(remaining_count) => remaining_count,
+            None if self.is_lossy => return Ok('\u{FFFD}'),
+            None => return Err(CharacterError::invalid_start(first_byte)),
+        };
+
+        let bytes = match self.read_sequence(reader, first_byte, remaining_count) {
+            Err(CharacterError::TruncatedSequence { .. }) if self.is_lossy => {
+                return Ok('\u{FFFD}')
+            }
+            result => result?,
+        };
+
+        if bytes == [0xC0, 0x80] {
+            return Ok('\0');
+        }
+
+        if is_encoded_surrogate(&bytes) {
+            let high = surrogate_code_point(&bytes);
+            if high >= 0xDC00 {
+                return self.lone_surrogate(bytes);
+            }
+
+            return match self.read_low_surrogate(reader)? {
+                Some(low) => {
+                    let code_point =
+                        0x10000 + (((high as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+                    Ok(char::from_u32(code_point).unwrap_or('\u{FFFD}'))
+                }
+                None => self.lone_surrogate(bytes),
+            };
+        }
+
+        match simdutf8::basic::from_utf8(&bytes) {
+            Ok(string) => Ok(string.chars().next().unwrap_or('\u{FFFD}')),
+            Err(_) if self.is_lossy => Ok('\u{FFFD}'),
+            Err(_) => Err(CharacterError::invalid_utf8(bytes)),
+        }
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DecoderCharacterStream;
+
+    #[test]
+    fn cesu8_test() {
+        // U+1F4BB as a surrogate pair, a Modified UTF-8 NUL, and a lone high surrogate.
+        let bytes = b"a\xED\xA0\xBD\xED\xB2\xBB\xC0\x80\xED\xA0\xBDb";
+        let read: Vec<CharacterStreamResult> =
+            DecoderCharacterStream::new(Cursor::new(bytes), Cesu8Decoder::new(false))
+                .into_iter()
+                .collect();
+
+        assert_eq!(read[0].as_ref().unwrap(), &'a');
+        assert_eq!(read[1].as_ref().unwrap(), &'\u{1F4BB}');
+        assert_eq!(read[2].as_ref().unwrap(), &'\0');
+        assert!(matches!(
+            read[3],
+            Err(CharacterError::Surrogate {
+                code_point: 0xD83D,
+                ..
+            })
+        ));
+        assert_eq!(read[4].as_ref().unwrap(), &'b');
+    }
+
+    #[test]
+    fn truncated_test() {
+        let read = |is_lossy| -> Vec<CharacterStreamResult> {
+            DecoderCharacterStream::new(Cursor::new(b"a\xED\xA0"), Cesu8Decoder::new(is_lossy))
+                .into_iter()
+                .collect()
+        };
+
+        let lossy = read(true);
+        assert_eq!(lossy.len(), 2);
+        assert_eq!(lossy[1].as_ref().unwrap(), &'\u{FFFD}');
+
+        assert!(matches!(
+            read(false)[1],
+            Err(CharacterError::TruncatedSequence {
+                expected: 3,
+                got: 2,
+                ..
+            })
+        ));
+    }
+}
@@ -1,8 +1,10 @@
-use std::{error::Error, io::Read};
+use std::{error::Error, io::Read, time::Duration};
 
 use crate::{
-    CharStream, CharacterStream, CharacterStreamResult, MultiPeek, MultiPeekable, Peek, Peekable,
-    PeekableCharacterStream, ToCharacterStream, TryToCharacterStream,
+    CharStream, CharacterError, CharacterStream, CharacterStreamResult, CollapseWhitespace,
+    Contextualized, Indented, Lines, MultiPeek, MultiPeekable, NormalizeNewlines, Peek, Peekable,
+    PeekableCharacterStream, RawBytes, Spanned, Split, SplitStr, StreamStats, StripComments,
+    ToCharacterStream, TryToCharacterStream, Words,
 };
 
 pub(crate) const INTERRUPTED_MAX: usize = 5;
@@ -14,6 +16,14 @@ pub struct CharacterIterator<Stream: CharStream> {
     /// Maximum amount of [Interrupted](std::io::ErrorKind::Interrupted) errors.
     pub(crate) interrupted_max: usize,
     pub interrupted_count: usize,
+    /// Total amount of [Interrupted](std::io::ErrorKind::Interrupted) errors that were retried.
+    pub(crate) interrupted_retries: usize,
+    /// The amount of characters yielded.
+    pub(crate) chars_read: usize,
+    /// Called with the current count every time an interrupted read is retried.
+    pub(crate) on_interrupted: Option<Box<dyn FnMut(usize) + Send>>,
+    /// Returns how long to sleep before an interrupted read is retried.
+    pub(crate) backoff: Option<Box<dyn Fn(usize) -> Duration + Send>>,
 }
 
 impl<Stream: CharStream> CharacterIterator<Stream> {
@@ -23,9 +33,60 @@ impl<Stream: CharStream> CharacterIterator<Stream> {
             stream,
             interrupted_max,
             interrupted_count: 0,
+            interrupted_retries: 0,
+            chars_read: 0,
+            on_interrupted: None,
+            backoff: None,
         }
     }
 
+    /// Replace the underlying stream, keeping the interrupt state.
+    pub(crate) fn map_stream<Other: CharStream>(
+        self,
+        map: impl FnOnce(Stream) -> Other,
+    ) -> CharacterIterator<Other> {
+        CharacterIterator {
+            stream: map(self.stream),
+            interrupted_max: self.interrupted_max,
+            interrupted_count: self.interrupted_count,
+            interrupted_retries: self.interrupted_retries,
+            chars_read: self.chars_read,
+            on_interrupted: self.on_interrupted,
+            backoff: self.backoff,
+        }
+    }
+
+    /// The amount of characters yielded so far.
+    pub fn chars_read(&self) -> usize {
+        self.chars_read
+    }
+
+    /// Amount of consecutive [Interrupted](std::io::ErrorKind::Interrupted) errors that have been retried.
+    pub fn interrupted_count(&self) -> usize {
+        self.interrupted_count
+    }
+
+    /// Reset the count of consecutive [Interrupted](std::io::ErrorKind::Interrupted) errors.
+    pub fn reset_interrupted(&mut self) {
+        self.interrupted_count = 0;
+    }
+
+    /// Set a callback that is called with the current count every time an
+    /// [Interrupted](std::io::ErrorKind::Interrupted) read is retried.
+    ///
+    /// The callback runs before the delay set with [backoff](CharacterIterator::backoff), if any.
+    pub fn on_interrupted(&mut self, callback: impl FnMut(usize) + Send + 'static) {
+        self.on_interrupted = Some(Box::new(callback));
+    }
+
+    /// Sleep for `delay(count)` before retrying an [Interrupted](std::io::ErrorKind::Interrupted)
+    /// read, where `count` is the amount of consecutive interruptions so far.
+    ///
+    /// Independent of [on_interrupted](CharacterIterator::on_interrupted), both can be set at once.
+    pub fn backoff(&mut self, delay: impl Fn(usize) -> Duration + Send + 'static) {
+        self.backoff = Some(Box::new(delay));
+    }
+
     /// Return a reference to the underlying stream.
     pub fn stream(&self) -> &Stream {
         &self.stream
@@ -40,28 +101,114 @@ impl<Stream: CharStream> CharacterIterator<Stream> {
     pub fn is_lossy(&self) -> bool {
         self.stream.is_lossy()
     }
+
+    /// Split the characters into fields separated by `delimiter`.
+    pub fn split(self, delimiter: char) -> Split<Stream> {
+        Split::new(self, delimiter)
+    }
+
+    /// Split the characters into fields separated by `delimiter`.
+    pub fn split_str(self, delimiter: &str) -> SplitStr<Stream> {
+        SplitStr::new(self, delimiter)
+    }
+
+    /// Read the characters word by word, skipping runs of whitespace.
+    pub fn words(self) -> Words<Stream> {
+        Words::new(self)
+    }
+
+    /// Read the characters line by line.
+    pub fn lines(self) -> Lines<Stream> {
+        Lines::new(self)
+    }
+
+    /// Attach `label` as context to decode errors.
+    pub fn context(self, label: &'static str) -> Contextualized<Stream> {
+        Contextualized::new(self, label)
+    }
+
+    /// Convert `\r\n` and lone `\r` into `\n`.
+    pub fn normalize_newlines(self) -> NormalizeNewlines<Stream> {
+        NormalizeNewlines::new(self)
+    }
+
+    /// Collapse every run of whitespace into a single space.
+    pub fn collapse_whitespace(self) -> CollapseWhitespace<Stream> {
+        CollapseWhitespace::new(self)
+    }
+
+    /// Remove comments, keeping the original position of every remaining character.
+    pub fn strip_comments(self) -> StripComments<Stream> {
+        StripComments::new(self)
+    }
+
+    /// Replace the leading whitespace of each line with indentation events.
+    pub fn indented(self) -> Indented<Stream> {
+        Indented::new(self)
+    }
 }
 
 impl<Reader: Read> CharacterIterator<CharacterStream<Reader>> {
+    /// Snapshot of what the underlying stream has done so far, including retried interrupts.
+    pub fn stats(&self) -> StreamStats {
+        StreamStats {
+            interrupted_retries: self.interrupted_retries,
+            ..self.stream.stats()
+        }
+    }
+
+    /// Pair each character with the [Span](crate::Span) it was found at.
+    pub fn spanned(self) -> Spanned<Reader> {
+        Spanned::new(self)
+    }
+
+    /// Pair each character with the raw bytes it was decoded from.
+    pub fn with_raw_bytes(self) -> RawBytes<Reader> {
+        RawBytes::new(self)
+    }
+
     /// Make the underlying stream peekable.
     pub fn peek(self) -> CharacterIterator<PeekableCharacterStream<Reader, Peek>> {
-        CharacterIterator::new(self.stream.peeky(), self.interrupted_max)
+        self.map_stream(CharacterStream::peeky)
     }
 
     /// Make the underlying stream multi-peekable
     pub fn peek_multi(self) -> CharacterIterator<PeekableCharacterStream<Reader, MultiPeek>> {
-        CharacterIterator::new(self.stream.peeky_multi(), self.interrupted_max)
+        self.map_stream(CharacterStream::peeky_multi)
     }
 }
 
-impl<Reader: Read> CharacterIterator<PeekableCharacterStream<Reader, Peek>> {
+impl<Reader: Read, Stream: CharStream>
+    CharacterIterator<PeekableCharacterStream<Reader, Peek, Stream>>
+{
     /// Peek the next character in the stream.
     pub fn peek(&mut self) -> Option<&<Self as Iterator>::Item> {
         self.stream.peek()
     }
+
+    /// Consume the next character if it satisfies `predicate`, like [std::iter::Peekable::next_if].
+    ///
+    /// If the next item is an error, it is consumed and returned.
+    pub fn next_if(
+        &mut self,
+        predicate: impl FnOnce(&char) -> bool,
+    ) -> Option<<Self as Iterator>::Item> {
+        match self.peek() {
+            Some(Ok(character)) if predicate(character) => self.next(),
+            Some(Err(_)) => self.next(),
+            _ => None,
+        }
+    }
+
+    /// Consume the next character if it is equal to `expected`.
+    pub fn next_if_eq(&mut self, expected: char) -> Option<<Self as Iterator>::Item> {
+        self.next_if(|character| *character == expected)
+    }
 }
 
-impl<Reader: Read> CharacterIterator<PeekableCharacterStream<Reader, MultiPeek>> {
+impl<Reader: Read, Stream: CharStream>
+    CharacterIterator<PeekableCharacterStream<Reader, MultiPeek, Stream>>
+{
     /// Peek the next character in the stream. (multi-peek)
     pub fn peek(&mut self) -> Option<&<Self as Iterator>::Item> {
         self.stream.peek()
@@ -70,11 +217,92 @@ impl<Reader: Read> CharacterIterator<PeekableCharacterStream<Reader, MultiPeek>>
     pub fn reset_peek(&mut self) {
         self.stream.reset_peek()
     }
+
+    /// Consume the next character if it satisfies `predicate`, like [std::iter::Peekable::next_if].
+    ///
+    /// If the next item is an error, it is consumed and returned. The peek position is reset.
+    pub fn next_if(
+        &mut self,
+        predicate: impl FnOnce(&char) -> bool,
+    ) -> Option<<Self as Iterator>::Item> {
+        self.reset_peek();
+        let matches = match self.peek() {
+            Some(Ok(character)) => predicate(character),
+            Some(Err(_)) => true,
+            None => false,
+        };
+        self.reset_peek();
+
+        if matches {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Consume the next character if it is equal to `expected`.
+    ///
+    /// The peek position is reset.
+    pub fn next_if_eq(&mut self, expected: char) -> Option<<Self as Iterator>::Item> {
+        self.next_if(|character| *character == expected)
+    }
 }
 
 impl<Stream: CharStream + std::fmt::Debug> std::fmt::Debug for CharacterIterator<Stream> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        f.debug_struct("CharacterIterator")
+            .field("stream", &self.stream)
+            .field("interrupted_max", &self.interrupted_max)
+            .field("interrupted_count", &self.interrupted_count)
+            .finish()
+    }
+}
+
+impl<Stream: CharStream> CharacterIterator<Stream> {
+    /// Read the next item using `read`, retrying on [Interrupted](std::io::ErrorKind::Interrupted)
+    /// errors and treating the end of the stream as `None`.
+    pub(crate) fn next_with<T>(
+        &mut self,
+        mut read: impl FnMut(&mut Stream) -> Result<T, CharacterError>,
+    ) -> Option<Result<T, CharacterError>> {
+        loop {
+            let error = match read(&mut self.stream) {
+                Ok(item) => {
+                    self.interrupted_count = 0;
+                    self.chars_read += 1;
+
+                    return Some(Ok(item));
+                }
+                Err(error) => error,
+            };
+
+            match error {
+                CharacterError::NoBytesRead => return None,
+                CharacterError::IoError { ref error, .. }
+                    if error.kind() == std::io::ErrorKind::Interrupted =>
+                {
+                    if self.interrupted_count > self.interrupted_max {
+                        let count = std::mem::take(&mut self.interrupted_count);
+                        return Some(Err(CharacterError::TooManyInterrupts(count)));
+                    }
+
+                    self.interrupted_count += 1;
+                    self.interrupted_retries += 1;
+                    if let Some(on_interrupted) = &mut self.on_interrupted {
+                        on_interrupted(self.interrupted_count);
+                    }
+                    if let Some(backoff) = &self.backoff {
+                        std::thread::sleep(backoff(self.interrupted_count));
+                    }
+                }
+                CharacterError::IoError { ref error, .. }
+                    if error.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return None
+                }
+                other => return Some(Err(other)),
+            }
+        }
     }
 }
 
@@ -82,33 +310,15 @@ impl<Stream: CharStream> Iterator for CharacterIterator<Stream> {
     type Item = CharacterStreamResult;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.stream.read_char() {
-            Ok(character) => {
-                if self.interrupted_count > 0 {
-                    self.interrupted_count = 0;
-                }
+        self.next_with(|stream| stream.read_char())
+    }
 
-                Some(Ok(character))
-            }
-            Err(error) => match error {
-                crate::CharacterError::NoBytesRead => None,
-                crate::CharacterError::IoError {
-                    bytes: _,
-                    error: ref err,
-                } => match err.kind() {
-                    std::io::ErrorKind::Interrupted => {
-                        if self.interrupted_count <= self.interrupted_max {
-                            self.interrupted_count += 1;
-                            self.next()
-                        } else {
-                            None
-                        }
-                    }
-                    std::io::ErrorKind::UnexpectedEof => None,
-                    _ => Some(Err(error)),
-                },
-                other => Some(Err(other)),
-            },
+    /// Every character takes between one and four bytes, so if the stream knows how many bytes
+    /// are left, that bounds the amount of characters.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.stream.bytes_remaining() {
+            Some(bytes) => (bytes / 4, Some(bytes)),
+            None => (0, None),
         }
     }
 }
@@ -160,3 +370,187 @@ impl<Reader: Read, T: TryToCharacterStream<Reader>> TryToCharacterIterator<Reade
         Ok(self.try_to_character_stream_lossy()?.into_iter())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Cursor, Read},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::{
+        CharacterError, CharacterIterator, CharacterStream, Contextualized, Lines, Split,
+        StreamStats, ToCharacterIterator, Words, INTERRUPTED_MAX,
+    };
+
+    /// Reader that fails with [Interrupted](io::ErrorKind::Interrupted) a set amount of times.
+    struct InterruptingReader {
+        interruptions: usize,
+        inner: Cursor<&'static [u8]>,
+    }
+
+    impl Read for InterruptingReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.interruptions > 0 {
+                self.interruptions -= 1;
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn on_interrupted_test() {
+        let reader = InterruptingReader {
+            interruptions: 2,
+            inner: Cursor::new(b"ab"),
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut iter = CharacterStream::from(reader).into_iter();
+
+        let counter = calls.clone();
+        iter.on_interrupted(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert_eq!(iter.next().unwrap().unwrap(), 'a');
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert_eq!(iter.interrupted_count(), 0);
+        assert_eq!(iter.next().unwrap().unwrap(), 'b');
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn next_if_test() {
+        let mut iter = b"ab\x80".to_character_iterator().peek();
+
+        assert_eq!(iter.next_if_eq('a').unwrap().unwrap(), 'a');
+        assert!(iter.next_if_eq('a').is_none());
+        assert_eq!(
+            iter.next_if(char::is_ascii_lowercase).unwrap().unwrap(),
+            'b'
+        );
+        assert!(iter.next_if(|_| false).unwrap().is_err());
+        assert!(iter.next_if(|_| true).is_none());
+
+        let mut iter = "ab".to_character_iterator().peek_multi();
+        iter.peek();
+        assert!(iter.next_if_eq('b').is_none());
+        assert_eq!(iter.next_if_eq('a').unwrap().unwrap(), 'a');
+        assert_eq!(iter.next().unwrap().unwrap(), 'b');
+    }
+
+    #[test]
+    fn stats_test() {
+        let reader = InterruptingReader {
+            interruptions: 1,
+            inner: Cursor::new(b"a\xF0\x9F\x92\xBB\x80\xE2\x28\xA1"),
+        };
+        let mut iter = CharacterStream::new(reader, true).into_iter();
+        while iter.next().is_some() {}
+
+        assert_eq!(
+            iter.stats(),
+            StreamStats {
+                bytes_read: 9,
+                chars_decoded: 6,
+                replacements: 3,
+                interrupted_retries: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn size_hint_test() {
+        let mut iter = "ab\u{1F4BB}".to_character_iterator();
+        assert_eq!(iter.size_hint(), (1, Some(6)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (1, Some(5)));
+        iter.next();
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+
+        use crate::CharStream;
+
+        let mut cursor = Cursor::new(b"0123456789".to_vec());
+        cursor.set_position(2);
+        let mut character_stream = CharacterStream::from(cursor);
+        assert_eq!(character_stream.measure_remaining().unwrap(), 8);
+        character_stream.read_char().unwrap();
+        assert_eq!(character_stream.measure_remaining().unwrap(), 7);
+        assert_eq!(character_stream.into_iter().size_hint(), (1, Some(7)));
+
+        let reader: &[u8] = b"unknown";
+        assert_eq!(
+            CharacterStream::from(reader).into_iter().size_hint(),
+            (0, None)
+        );
+    }
+
+    #[test]
+    fn too_many_interrupts_test() {
+        let reader = InterruptingReader {
+            interruptions: 10,
+            inner: Cursor::new(b"a"),
+        };
+        let delays = Arc::new(AtomicUsize::new(0));
+        let mut iter = CharacterStream::new(reader, false).into_iter();
+
+        let counter = delays.clone();
+        iter.backoff(move |count| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Duration::from_micros(count as u64)
+        });
+
+        assert!(matches!(
+            iter.next(),
+            Some(Err(CharacterError::TooManyInterrupts(6)))
+        ));
+        assert_eq!(delays.load(Ordering::Relaxed), INTERRUPTED_MAX + 1);
+        assert_eq!(iter.interrupted_count(), 0);
+        assert_eq!(iter.next().unwrap().unwrap(), 'a');
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn send_test() {
+        fn assert_send<T: Send>() {}
+
+        type Stream = CharacterStream<Cursor<Vec<u8>>>;
+        assert_send::<CharacterIterator<Stream>>();
+        assert_send::<Split<Stream>>();
+        assert_send::<Lines<Stream>>();
+        assert_send::<Words<Stream>>();
+        assert_send::<Contextualized<Stream>>();
+    }
+
+    #[test]
+    fn backoff_with_callback_test() {
+        let reader = InterruptingReader {
+            interruptions: 3,
+            inner: Cursor::new(b"a"),
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let delays = Arc::new(AtomicUsize::new(0));
+        let mut iter = CharacterStream::new(reader, false).into_iter();
+
+        let counter = calls.clone();
+        iter.on_interrupted(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        let counter = delays.clone();
+        iter.backoff(move |count| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Duration::from_micros(count as u64)
+        });
+
+        assert_eq!(iter.next().unwrap().unwrap(), 'a');
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        assert_eq!(delays.load(Ordering::Relaxed), 3);
+    }
+}
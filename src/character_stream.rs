@@ -2,14 +2,17 @@ use std::{
     collections::VecDeque,
     error::Error,
     fs::File,
-    io::{self, BufReader, Cursor, Read},
+    io::{self, BufReader, Cursor, Read, Seek, SeekFrom},
     marker::PhantomData,
-    ops::{Deref, DerefMut},
+    ops::Range,
 };
 
 use anyhow::anyhow;
 
-use crate::{CharacterError, CharacterIterator, MultiPeek, Peek, INTERRUPTED_MAX};
+use crate::{
+    CharacterError, CharacterIterator, Checkpoint, Chunks, Decoded, FixedPeekableCharacterStream,
+    MultiPeek, Peek, Position, PositionedCharacterStream, TakeChars, Utf8Decoder, INTERRUPTED_MAX,
+};
 
 pub trait Peekable<T> {
     fn peek(&mut self) -> Option<&T>;
@@ -23,6 +26,294 @@ pub trait MultiPeekable<T> {
 pub trait CharStream {
     fn read_char(&mut self) -> CharacterStreamResult;
     fn is_lossy(&self) -> bool;
+
+    /// Amount of bytes consumed from the underlying reader, if the stream keeps track of it.
+    fn bytes_read(&self) -> Option<usize> {
+        None
+    }
+
+    /// Amount of bytes left to decode, if the stream knows it.
+    fn bytes_remaining(&self) -> Option<usize> {
+        None
+    }
+
+    /// The [Position] of the next character, if the stream keeps track of it.
+    fn current_position(&self) -> Option<Position> {
+        None
+    }
+
+    /// Attempts to read a character from the stream, treating the end of the stream as `Ok(None)`.
+    ///
+    /// Decode errors and any other IO errors are still returned as an error.
+    fn read_char_or_eof(&mut self) -> Result<Option<char>, CharacterError> {
+        match self.read_char() {
+            Ok(character) => Ok(Some(character)),
+            Err(CharacterError::NoBytesRead) => Ok(None),
+            Err(CharacterError::IoError { error, .. })
+                if error.kind() == io::ErrorKind::UnexpectedEof =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Reads up to the first `delimiter`, which is consumed.
+    ///
+    /// If `inclusive` is set, the delimiter is included in the returned text.
+    fn read_until(&mut self, delimiter: char, inclusive: bool) -> Result<Delimited, CharacterError>
+    where
+        Self: Sized,
+    {
+        let mut text = String::new();
+
+        while let Some(character) = self.read_char_or_eof()? {
+            if character == delimiter {
+                if inclusive {
+                    text.push(character);
+                }
+                return Ok(Delimited::Found(text));
+            }
+            text.push(character);
+        }
+
+        Ok(Delimited::Eof(text))
+    }
+
+    /// Reads up to the first occurrence of `delimiter`, which is consumed.
+    ///
+    /// If `inclusive` is set, the delimiter is included in the returned text.
+    fn read_until_str(
+        &mut self,
+        delimiter: &str,
+        inclusive: bool,
+    ) -> Result<Delimited, CharacterError>
+    where
+        Self: Sized,
+    {
+        let mut text = String::new();
+
+        if delimiter.is_empty() {
+            return Ok(Delimited::Found(text));
+        }
+
+        while let Some(character) = self.read_char_or_eof()? {
+            text.push(character);
+            if text.ends_with(delimiter) {
+                if !inclusive {
+                    text.truncate(text.len() - delimiter.len());
+                }
+                return Ok(Delimited::Found(text));
+            }
+        }
+
+        Ok(Delimited::Eof(text))
+    }
+}
+
+/// Text read by [CharStream::read_until] or [CharStream::read_until_str].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Delimited {
+    /// The delimiter was found.
+    Found(String),
+    /// The stream ended before the delimiter was found.
+    Eof(String),
+}
+
+impl Delimited {
+    /// Whether the delimiter was found.
+    pub fn is_found(&self) -> bool {
+        matches!(self, Delimited::Found(_))
+    }
+
+    /// The text that was read.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Delimited::Found(text) | Delimited::Eof(text) => text,
+        }
+    }
+
+    /// Returns the text that was read.
+    pub fn into_string(self) -> String {
+        match self {
+            Delimited::Found(text) | Delimited::Eof(text) => text,
+        }
+    }
+}
+
+/// A [CharStream] that can look ahead, implemented by both [Peek] and [MultiPeek] streams.
+///
+/// The trait is object safe, so parsers can take a `&mut dyn PeekCharStream`.
+pub trait PeekCharStream: CharStream {
+    /// Peek the character `n` positions ahead, where 0 is the next character.
+    ///
+    /// Returns `None` if the stream ends before that character.
+    fn peek_nth(&mut self, n: usize) -> Option<&CharacterStreamResult>;
+
+    /// Peek the next character by value, so the stream isn't kept borrowed.
+    ///
+    /// IO errors and other errors are recreated from their kind and message.
+    fn peek_copied(&mut self) -> Option<CharacterStreamResult> {
+        let peeked = self.peek_nth(0)?;
+        Some(
+            peeked
+                .as_ref()
+                .map(|character| *character)
+                .map_err(CharacterError::duplicate),
+        )
+    }
+
+    /// Consume characters while they satisfy `predicate`, returning them as a string.
+    ///
+    /// The first character that doesn't match is left unread. Reading stops at the end of the
+    /// stream, or at an error which is consumed and returned.
+    fn take_while(
+        &mut self,
+        mut predicate: impl FnMut(char) -> bool,
+    ) -> Result<String, CharacterError>
+    where
+        Self: Sized,
+    {
+        let mut taken = String::new();
+
+        loop {
+            match self.peek_copied() {
+                Some(Ok(character)) if predicate(character) => {
+                    self.read_char()?;
+                    taken.push(character);
+                }
+                Some(Err(error)) => {
+                    if !matches!(error, CharacterError::PeekLimitExceeded(_)) {
+                        let _ = self.read_char();
+                    }
+                    return Err(error);
+                }
+                _ => return Ok(taken),
+            }
+        }
+    }
+
+    /// Consume and discard characters while they satisfy `predicate`.
+    ///
+    /// Stops at the first character that doesn't match, the end of the stream or an error, all of
+    /// which are left unread.
+    ///
+    /// Returns the amount of characters skipped.
+    fn skip_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> usize
+    where
+        Self: Sized,
+    {
+        let mut skipped = 0;
+
+        while matches!(self.peek_nth(0), Some(Ok(character)) if predicate(*character)) {
+            let _ = self.read_char();
+            skipped += 1;
+        }
+
+        skipped
+    }
+
+    /// Consume `prefix` if the stream continues with it, returning whether it did.
+    ///
+    /// If the upcoming characters don't match, nothing is consumed.
+    fn consume_str(&mut self, prefix: &str) -> bool
+    where
+        Self: Sized,
+    {
+        let matches = prefix
+            .chars()
+            .enumerate()
+            .all(|(n, expected)| matches!(self.peek_nth(n), Some(Ok(character)) if *character == expected));
+
+        if matches {
+            for _ in prefix.chars() {
+                let _ = self.read_char();
+            }
+        }
+
+        matches
+    }
+
+    /// Consume the next character if it is `expected`.
+    ///
+    /// Otherwise [CharacterError::Unexpected] is returned and nothing is consumed.
+    /// Decode errors are consumed and returned as is.
+    fn expect(&mut self, expected: char) -> Result<(), CharacterError>
+    where
+        Self: Sized,
+    {
+        let found = match self.peek_copied() {
+            Some(Ok(character)) if character == expected => {
+                self.read_char()?;
+                return Ok(());
+            }
+            Some(Ok(character)) => Some(character),
+            Some(Err(error)) => {
+                if !matches!(error, CharacterError::PeekLimitExceeded(_)) {
+                    let _ = self.read_char();
+                }
+                return Err(error);
+            }
+            None => None,
+        };
+
+        Err(CharacterError::Unexpected {
+            expected: expected.to_string(),
+            found,
+            position: self.current_position(),
+        })
+    }
+
+    /// Consume `expected` if the stream continues with it.
+    ///
+    /// Otherwise [CharacterError::Unexpected] is returned with the first character that differs,
+    /// and nothing is consumed. Like [expect](PeekCharStream::expect), a decode error is consumed
+    /// along with the characters before it and returned as is.
+    fn expect_str(&mut self, expected: &str) -> Result<(), CharacterError>
+    where
+        Self: Sized,
+    {
+        for (n, expected_character) in expected.chars().enumerate() {
+            let found = match self.peek_nth(n) {
+                Some(Ok(character)) if *character == expected_character => continue,
+                Some(Ok(character)) => Some(*character),
+                Some(Err(error)) => {
+                    let error = error.duplicate();
+                    if !matches!(error, CharacterError::PeekLimitExceeded(_)) {
+                        for _ in 0..=n {
+                            let _ = self.read_char();
+                        }
+                    }
+                    return Err(error);
+                }
+                None => None,
+            };
+
+            return Err(CharacterError::Unexpected {
+                expected: expected.to_string(),
+                found,
+                position: self.current_position(),
+            });
+        }
+
+        for _ in expected.chars() {
+            self.read_char()?;
+        }
+
+        Ok(())
+    }
+
+    /// Consume and discard whitespace, returning the amount of characters skipped.
+    fn skip_whitespace(&mut self) -> usize
+    where
+        Self: Sized,
+    {
+        self.skip_while(|character| {
+            // Most whitespace is ASCII, so check that before the full Unicode table.
+            matches!(character, ' ' | '\t' | '\n' | '\r' | '\x0B' | '\x0C')
+                || (!character.is_ascii() && character.is_whitespace())
+        })
+    }
 }
 
 /// A result that contains a parsed character or a [CharacterStreamError].
@@ -36,16 +327,102 @@ pub type CharacterStreamResult = Result<char, CharacterError>;
 /// This wrapper does NOT parse graphemes.
 pub struct CharacterStream<Reader: Read> {
     /// The stream from which the incoming bytes are from.
-    pub stream: Reader,
+    ///
+    /// Bytes are read from it in blocks, so it may be ahead of the decoded characters.
+    pub(crate) stream: Reader,
     /// Whether or not we should care whether invalid bytes are detected.
     ///
     /// If `true`, then invalid byte sequences will be replaced with a U+FFFD.
     ///
     /// If `false`, then an error will be returned.
     pub is_lossy: bool,
+    /// Optional rule that is called with every decoded character.
+    ///
+    /// If it returns a reason, then the character is returned as a
+    /// [Rejected](CharacterError::Rejected) error instead.
+    pub classify: Option<fn(char) -> Option<&'static str>>,
+    /// How encoded surrogate code points (U+D800 to U+DFFF) are handled.
+    pub surrogate_policy: SurrogatePolicy,
+    /// The amount of surrogates that were replaced under [SurrogatePolicy::PreserveAsReplacement].
+    pub replaced_surrogates: usize,
+    /// The amount of bytes read from the stream.
+    pub(crate) bytes_read: usize,
+    /// The amount of characters decoded from the stream.
+    pub(crate) chars_decoded: usize,
+    /// The amount of invalid byte sequences replaced with a U+FFFD.
+    pub(crate) replacements: usize,
+    /// Whether or not a leading U+FEFF byte order mark should be skipped.
+    pub strip_bom: bool,
+    /// Whether or not overlong sequences, encoded surrogates and code points above U+10FFFF are
+    /// reported with their own [error](CharacterError) variants.
+    pub strict: bool,
+    /// Whether or not the first character has been read.
+    pub(crate) started: bool,
+    /// The byte offsets of the most recently decoded character.
+    pub(crate) last_char_span: Option<Range<usize>>,
+    /// Whether or not a [WouldBlock](io::ErrorKind::WouldBlock) read is returned as
+    /// [CharacterError::WouldBlock], keeping the bytes of a partially read character.
+    pub nonblocking: bool,
+    /// The bytes of a character whose read was interrupted by a [WouldBlock](io::ErrorKind::WouldBlock).
+    pub(crate) pending: Vec<u8>,
+    /// Bytes read from the stream ahead of decoding.
+    buffer: Vec<u8>,
+    /// The position of the next unread byte in `buffer`.
+    buffer_position: usize,
+    /// The amount of bytes read from the stream at once.
+    buffer_capacity: usize,
+    /// The byte offset of the end of the stream, if known.
+    pub(crate) byte_length: Option<usize>,
+    /// The end of the bytes in `buffer` that are known to be valid UTF-8.
+    #[cfg(feature = "simd")]
+    validated_until: usize,
+}
+
+/// The amount of bytes a [CharacterStream] reads from its stream at once, unless configured otherwise.
+pub const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// Snapshot of what a [CharacterStream] has done so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamStats {
+    /// The amount of bytes read, which is also the current byte position.
+    pub bytes_read: usize,
+    /// The amount of characters decoded, including replacements.
+    pub chars_decoded: usize,
+    /// The amount of invalid byte sequences replaced with a U+FFFD.
+    pub replacements: usize,
+    /// The amount of [Interrupted](io::ErrorKind::Interrupted) reads that were retried.
+    pub interrupted_retries: usize,
+}
+
+/// Policy for three byte sequences that encode a surrogate code point, as found in WTF-8 and CESU-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SurrogatePolicy {
+    /// Treat surrogates as invalid UTF-8.
+    #[default]
+    Reject,
+    /// Replace surrogates with a U+FFFD.
+    Replace,
+    /// Replace surrogates with a U+FFFD, counting them in
+    /// [replaced_surrogates](CharacterStream::replaced_surrogates).
+    PreserveAsReplacement,
+    /// Accept lone surrogates like WTF-8 does, returning each as a
+    /// [Surrogate](CharacterError::Surrogate) error without stopping iteration.
+    Wtf8,
+}
+
+/// The surrogate code point encoded by a three byte sequence.
+pub(crate) fn surrogate_code_point(bytes: &[u8]) -> u16 {
+    (((bytes[0] & 0x0F) as u16) << 12)
+        | (((bytes[1] & 0x3F) as u16) << 6)
+        | (bytes[2] & 0x3F) as u16
+}
+
+/// Does `bytes` encode a surrogate code point?
+pub(crate) fn is_encoded_surrogate(bytes: &[u8]) -> bool {
+    matches!(bytes, [0xED, 0xA0..=0xBF, 0x80..=0xBF])
 }
 
-fn remaining_byte_count(byte: u8) -> Option<usize> {
+pub(crate) fn remaining_byte_count(byte: u8) -> Option<usize> {
     let count = if (byte >> 7) == 0 {
         // Single byte character
         0
@@ -65,12 +442,90 @@ fn remaining_byte_count(byte: u8) -> Option<usize> {
     Some(count)
 }
 
+/// Fills `buf` from `reader`, looping over short reads.
+///
+/// Returns the amount of bytes read, which is only less than `buf.len()` if `reader` ended. On
+/// failure, the amount of bytes read before the error is returned along with it.
+///
+/// [Interrupted](io::ErrorKind::Interrupted) errors are only returned if no bytes were read yet.
+pub(crate) fn read_bytes_into(
+    reader: &mut impl Read,
+    buf: &mut [u8],
+) -> Result<usize, (usize, io::Error)> {
+    let mut filled = 0;
+
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted && filled > 0 => continue,
+            Err(error) => return Err((filled, error)),
+        }
+    }
+
+    Ok(filled)
+}
+
+/// Reads a set amount of bytes from `reader`.
+///
+/// See [CharacterStream::read_bytes].
+pub(crate) fn read_bytes(reader: &mut impl Read, amount: usize) -> Result<Vec<u8>, CharacterError> {
+    if amount == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut bytes = vec![0u8; amount];
+
+    match read_bytes_into(reader, &mut bytes) {
+        Ok(0) => Err(CharacterError::NoBytesRead),
+        Ok(len) if len != amount => {
+            bytes.truncate(len);
+            Err(CharacterError::Other {
+                bytes,
+                error: anyhow!("Failed to read the specified amount of bytes."),
+            })
+        }
+        Ok(_) => Ok(bytes),
+        Err((len, error)) => {
+            bytes.truncate(len);
+            Err(CharacterError::IoError { bytes, error })
+        }
+    }
+}
+
 impl<Reader: Read> CharacterStream<Reader> {
     /// Create a [CharacterStream] from a stream.
     ///
     /// Set `is_lossy` to `true` if you don't want to handle invalid byte sequences.
     pub fn new(stream: Reader, is_lossy: bool) -> Self {
-        Self { stream, is_lossy }
+        Self {
+            stream,
+            is_lossy,
+            classify: None,
+            surrogate_policy: SurrogatePolicy::default(),
+            replaced_surrogates: 0,
+            bytes_read: 0,
+            chars_decoded: 0,
+            replacements: 0,
+            strip_bom: false,
+            strict: false,
+            started: false,
+            last_char_span: None,
+            nonblocking: false,
+            pending: vec![],
+            buffer: vec![],
+            buffer_position: 0,
+            buffer_capacity: DEFAULT_BUFFER_CAPACITY,
+            byte_length: None,
+            #[cfg(feature = "simd")]
+            validated_until: 0,
+        }
+    }
+
+    /// Record that the stream holds `byte_length` bytes.
+    pub(crate) fn with_byte_length(mut self, byte_length: usize) -> Self {
+        self.byte_length = Some(byte_length);
+        self
     }
 
     /// Kinda builder pattern.
@@ -79,6 +534,104 @@ impl<Reader: Read> CharacterStream<Reader> {
         self
     }
 
+    /// Kinda builder pattern.
+    ///
+    /// Report overlong sequences, encoded surrogates and code points above U+10FFFF with their own
+    /// [error](CharacterError) variants.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Read up to `capacity` bytes from the stream at once, instead of [DEFAULT_BUFFER_CAPACITY].
+    /// A capacity of 1 reads byte by byte, never reading ahead of the decoded characters.
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity.max(1);
+        self
+    }
+
+    /// The amount of bytes read from the stream at once.
+    pub fn buffer_capacity(&self) -> usize {
+        self.buffer_capacity
+    }
+
+    /// Returns a reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader, as bytes are read from it in
+    /// blocks and it may be ahead of the decoded characters.
+    pub fn get_ref(&self) -> &Reader {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from or seek the underlying reader, as bytes are read
+    /// from it in blocks and the [buffered](CharacterStream::buffered) bytes would be skipped.
+    pub fn get_mut(&mut self) -> &mut Reader {
+        &mut self.stream
+    }
+
+    /// The bytes that were read from the stream ahead of decoding, but not consumed yet.
+    ///
+    /// Includes the bytes of a character whose read would have blocked.
+    pub fn buffered(&self) -> Vec<u8> {
+        let mut buffered = self.pending.clone();
+        buffered.extend_from_slice(&self.buffer[self.buffer_position..]);
+        buffered
+    }
+
+    /// Returns the underlying reader along with the [buffered](CharacterStream::buffered) bytes, which
+    /// come before whatever is read from the reader next.
+    pub fn into_inner(self) -> (Reader, Vec<u8>) {
+        let buffered = self.buffered();
+        (self.stream, buffered)
+    }
+
+    /// Returns a reader that starts right after the last decoded character, reading the
+    /// [buffered](CharacterStream::buffered) bytes before the rest of the underlying reader.
+    pub fn into_reader(self) -> io::Chain<Cursor<Vec<u8>>, Reader> {
+        let (stream, buffered) = self.into_inner();
+        Cursor::new(buffered).chain(stream)
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Return [CharacterError::WouldBlock] when the reader would block, so the read can be retried
+    /// once it is readable without losing the bytes of a partially read character.
+    pub fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Skip a leading U+FEFF byte order mark instead of returning it.
+    pub fn strip_bom(mut self, strip_bom: bool) -> Self {
+        self.strip_bom = strip_bom;
+        self
+    }
+
+    /// Kinda builder pattern.
+    pub fn surrogate_policy(mut self, surrogate_policy: SurrogatePolicy) -> Self {
+        self.surrogate_policy = surrogate_policy;
+        self
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Reject decoded characters for which `classify` returns a reason.
+    pub fn classify(mut self, classify: fn(char) -> Option<&'static str>) -> Self {
+        self.classify = Some(classify);
+        self
+    }
+
+    /// Wrap `self` into a [PositionedCharacterStream], tracking line and column.
+    pub fn positioned(self) -> PositionedCharacterStream<Self> {
+        PositionedCharacterStream::new(self)
+    }
+
     /// Wrap `self` into a single-peek [PeekableCharacterStream].
     pub fn peeky(self) -> PeekableCharacterStream<Reader, Peek> {
         self.into()
@@ -89,177 +642,900 @@ impl<Reader: Read> CharacterStream<Reader> {
         self.into()
     }
 
+    /// Wrap `self` into a [FixedPeekableCharacterStream] that can look up to `N` characters ahead.
+    pub fn peeky_fixed<const N: usize>(self) -> FixedPeekableCharacterStream<Self, N> {
+        self.into()
+    }
+
     /// Reads a set amount of bytes from the stream.
     ///
     /// Set `amount` to the amount of bytes you would like to read.
     ///
     /// Upon success, a [`Vec<u8>`] is returned, holding the read bytes.
+    /// Reading zero bytes always succeeds with an empty [`Vec<u8>`].
     ///
     /// Upon failure, an [error](CharacterError) is returned.
+    ///
+    /// [Interrupted](io::ErrorKind::Interrupted) errors are only returned if no bytes were read yet,
+    /// otherwise the read is retried so no bytes are lost.
     pub fn read_bytes(&mut self, amount: usize) -> Result<Vec<u8>, CharacterError> {
-        let handle = (&mut self.stream).take(amount as u64);
-        let result: Vec<Result<u8, io::Error>> = handle.bytes().collect();
-        let bytes: Vec<u8> = result
-            .iter()
-            .filter_map(|r| match r {
-                Ok(b) => Some(*b),
-                _ => None,
-            })
-            .collect();
-        let error = result.into_iter().find_map(|r| match r {
-            Err(error) => Some(error),
-            _ => None,
-        });
+        let result = read_bytes(&mut Buffered(self), amount);
+        self.bytes_read += match &result {
+            Ok(bytes) => bytes.len(),
+            Err(error) => error.bytes().map_or(0, <[u8]>::len),
+        };
 
-        match error {
-            Some(error) => Err(CharacterError::IoError { bytes, error }),
-            None => {
-                let len = bytes.len();
-                if len == 0 {
-                    Err(CharacterError::NoBytesRead)
-                } else if len != amount {
-                    Err(CharacterError::Other {
-                        bytes,
-                        error: anyhow!("Failed to read the specified amount of bytes."),
-                    })
-                } else {
-                    Ok(bytes)
-                }
+        result
+    }
+
+    /// Fills `buf` with bytes from the stream, without allocating.
+    ///
+    /// Returns the amount of bytes read, which is only less than `buf.len()` if the stream ended.
+    /// Readers that return fewer bytes than requested, such as sockets and pipes, are read from
+    /// until `buf` is full.
+    pub fn read_bytes_into(&mut self, buf: &mut [u8]) -> Result<usize, CharacterError> {
+        match read_bytes_into(&mut Buffered(self), buf) {
+            Ok(read) => {
+                self.bytes_read += read;
+                Ok(read)
+            }
+            Err((read, error)) => {
+                self.bytes_read += read;
+                Err(CharacterError::IoError {
+                    bytes: buf[..read].to_vec(),
+                    error,
+                })
             }
         }
     }
 
-    /// Reads a singluar byte from the stream.
-    pub fn read_byte(&mut self) -> Result<u8, CharacterError> {
-        Ok(self.read_bytes(1)?[0])
+    /// Iterate over chunks of valid text, as large as the internal buffer allows.
+    pub fn chunks(&mut self) -> Chunks<'_, Reader> {
+        Chunks::new(self)
     }
-}
 
-impl<Reader: Read> CharStream for CharacterStream<Reader> {
-    /// Attempts to read a character from the stream.
+    /// Iterate over at most `amount` characters, leaving the stream right after the last one.
     ///
-    /// If `is_lossy` is set to `true`, then invalid byte sequences will be a U+FFFD.
+    /// See [TakeChars] for how to continue with raw bytes afterwards.
+    pub fn take_chars(&mut self, amount: usize) -> TakeChars<'_, Reader> {
+        TakeChars::new(self, amount)
+    }
+
+    /// Consume `amount` characters without collecting them.
     ///
-    /// If `is_lossy` is set to `false`, then invalid byte sequences will be returned in addition to a parse error.
-    fn read_char(&mut self) -> CharacterStreamResult {
-        match self.read_byte() {
-            Ok(read_byte) => match remaining_byte_count(read_byte) {
-                Some(remaining_count) => {
-                    let mut bytes = vec![read_byte];
-                    if remaining_count > 0 {
-                        bytes.extend(self.read_bytes(remaining_count)?);
-                    }
-                    let chars: Vec<char> = match simdutf8::basic::from_utf8(&bytes) {
-                        Ok(string) => string.chars().collect(),
-                        Err(_) if self.is_lossy => vec!['\u{FFFD}'],
-                        Err(error) => {
-                            return Err(CharacterError::Other {
-                                bytes,
-                                error: anyhow!(error),
-                            })
-                        }
-                    };
+    /// Runs of ASCII are skipped straight from the buffer, other characters are decoded without
+    /// allocating.
+    ///
+    /// Returns the amount of characters skipped, which is only less than `amount` if the stream ended.
+    /// Upon failure, the amount of characters skipped before the error is returned along with it.
+    pub fn skip_chars(&mut self, amount: usize) -> Result<usize, (usize, CharacterError)> {
+        let mut skipped = 0;
 
-                    let len = chars.len();
+        while skipped < amount {
+            skipped += self.skip_ascii(amount - skipped);
+            if skipped == amount {
+                break;
+            }
 
-                    if len == 1 {
-                        Ok(chars[0])
-                    } else {
-                        Err(CharacterError::Other {
-                            bytes,
-                            error: anyhow!(format!("Expected 1 character, not {}", len)),
-                        })
-                    }
-                }
-                None => {
-                    if self.is_lossy {
-                        Ok('\u{FFFD}')
-                    } else {
-                        Err(CharacterError::Other {
-                            bytes: vec![read_byte],
-                            error: anyhow!("Invalid starting byte"),
-                        })
-                    }
-                }
-            },
-            Err(error) => return Err(error),
+            match self.read_char_raw() {
+                Ok(_) => skipped += 1,
+                Err(CharacterError::NoBytesRead) => break,
+                Err(error) => return Err((skipped, error)),
+            }
         }
-    }
 
-    fn is_lossy(&self) -> bool {
-        self.is_lossy
+        Ok(skipped)
     }
-}
 
-impl<Reader: std::fmt::Debug + Read> std::fmt::Debug for CharacterStream<Reader> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+    /// Skip past the first occurrence of `pattern`, searching the raw bytes so nothing is decoded.
+    ///
+    /// Returns the byte offset the match starts at, or `Ok(None)` if the stream ended without a
+    /// match, in which case everything was consumed. Invalid UTF-8 before the match is skipped.
+    pub fn skip_until_str(&mut self, pattern: &str) -> Result<Option<usize>, CharacterError> {
+        self.search(pattern.as_bytes())
     }
-}
 
-impl<Reader: Read> Deref for CharacterStream<Reader> {
-    type Target = Reader;
+    /// Skip to the first occurrence of `pattern`, leaving it as the next thing to be read.
+    ///
+    /// Returns the byte offset the match starts at, or `Ok(None)` if the stream ended without a
+    /// match, in which case everything was consumed. Invalid UTF-8 before the match is skipped.
+    pub fn find(&mut self, pattern: &str) -> Result<Option<usize>, CharacterError> {
+        let pattern = pattern.as_bytes();
+        let Some(offset) = self.search(pattern)? else {
+            return Ok(None);
+        };
 
-    fn deref(&self) -> &Self::Target {
-        &self.stream
-    }
-}
+        self.unread_bytes(pattern);
 
-impl<Reader: Read> DerefMut for CharacterStream<Reader> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.stream
+        Ok(Some(offset))
     }
-}
 
-impl<Reader: Read> AsRef<Reader> for CharacterStream<Reader> {
-    fn as_ref(&self) -> &Reader {
-        &*self
-    }
-}
+    /// Consume bytes up to and including the first occurrence of `pattern`, returning the byte
+    /// offset it starts at.
+    fn search(&mut self, pattern: &[u8]) -> Result<Option<usize>, CharacterError> {
+        self.started = true;
+        if pattern.is_empty() {
+            return Ok(Some(self.bytes_read));
+        }
 
-impl<Reader: Read> AsMut<Reader> for CharacterStream<Reader> {
-    fn as_mut(&mut self) -> &mut Reader {
-        &mut *self
-    }
-}
+        // Bytes of a partially read character are searched like any other unread bytes.
+        let pending = std::mem::take(&mut self.pending);
+        self.unread_bytes(&pending);
 
-impl<Reader: Read> From<Reader> for CharacterStream<Reader> {
-    fn from(reader: Reader) -> Self {
-        Self::new(reader, false)
-    }
-}
+        // The consumed bytes that may still be the start of a match.
+        let mut carry = vec![];
 
-pub struct PeekableCharacterStream<Reader: Read, PI> {
-    pub stream: CharacterStream<Reader>,
-    pub buffer: VecDeque<CharacterStreamResult>,
-    pub position: usize,
-    _phantom: PhantomData<PI>,
-}
+        loop {
+            match self.read_buffered(&mut []) {
+                Ok(_) => {}
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            }
 
-impl<Reader: Read, PI> PeekableCharacterStream<Reader, PI> {
-    pub fn new(stream: Reader, is_lossy: bool) -> Self {
-        Self {
-            stream: CharacterStream::new(stream, is_lossy),
-            buffer: VecDeque::new(),
-            position: 0,
-            _phantom: PhantomData,
+            let chunk_len = self.buffer.len() - self.buffer_position;
+            if chunk_len == 0 {
+                return Ok(None);
+            }
+
+            let start = self.bytes_read - carry.len();
+            let carried = carry.len();
+            let mut haystack = carry;
+            haystack.extend_from_slice(&self.buffer[self.buffer_position..]);
+
+            let found = haystack
+                .windows(pattern.len())
+                .position(|window| window == pattern);
+            let consumed = found.map_or(chunk_len, |position| position + pattern.len() - carried);
+            self.buffer_position += consumed;
+            self.bytes_read += consumed;
+
+            if let Some(position) = found {
+                return Ok(Some(start + position));
+            }
+
+            haystack.drain(..haystack.len().saturating_sub(pattern.len() - 1));
+            carry = haystack;
         }
     }
 
-    pub fn from_stream(stream: CharacterStream<Reader>) -> Self {
-        Self {
-            stream,
-            buffer: VecDeque::new(),
+    /// Put `bytes` back in front of the unread bytes.
+    fn unread_bytes(&mut self, bytes: &[u8]) {
+        let start = self.buffer_position.saturating_sub(bytes.len());
+        if self.buffer.get(start..self.buffer_position) == Some(bytes) {
+            self.buffer_position = start;
+        } else {
+            self.buffer.splice(
+                self.buffer_position..self.buffer_position,
+                bytes.iter().copied(),
+            );
+            #[cfg(feature = "simd")]
+            {
+                self.validated_until = 0;
+            }
+        }
+        self.bytes_read -= bytes.len();
+    }
+
+    /// Reads a singluar byte from the stream.
+    pub fn read_byte(&mut self) -> Result<u8, CharacterError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Attempts to read a character from the stream, along with the bytes it was decoded from.
+    ///
+    /// In lossy mode, a U+FFFD is paired with the invalid bytes it replaced.
+    pub fn read_char_with_bytes(&mut self) -> Result<(char, Vec<u8>), CharacterError> {
+        self.read_char_raw()
+            .map(|(character, bytes, len)| (character, bytes[..len].to_vec()))
+    }
+
+    /// Reads up to `amount` characters, stopping early if the stream ends.
+    pub fn read_chars(&mut self, amount: usize) -> Result<String, CharacterError> {
+        let mut string = String::with_capacity(amount);
+
+        for _ in 0..amount {
+            match self.read_char_raw() {
+                Ok((character, ..)) => string.push(character),
+                Err(CharacterError::NoBytesRead) => break,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(string)
+    }
+
+    /// Reads the rest of the stream into a [String].
+    ///
+    /// Valid UTF-8 is taken from the internal buffer a block at a time.
+    ///
+    /// On failure, the string read so far is returned along with the [error](CharacterError).
+    pub fn read_to_string(&mut self) -> Result<String, (String, CharacterError)> {
+        let mut string = String::new();
+
+        loop {
+            self.read_valid_chunk(&mut string);
+
+            match self.read_char_raw() {
+                Ok((character, ..)) => string.push(character),
+                Err(CharacterError::NoBytesRead) => return Ok(string),
+                Err(error) => return Err((string, error)),
+            }
+        }
+    }
+
+    /// Reads the rest of the stream into a [String], replacing invalid byte sequences with a U+FFFD.
+    ///
+    /// See [CharacterStream::read_to_string].
+    pub fn read_to_string_lossy(&mut self) -> Result<String, (String, CharacterError)> {
+        let is_lossy = std::mem::replace(&mut self.is_lossy, true);
+        let result = self.read_to_string();
+        self.is_lossy = is_lossy;
+
+        result
+    }
+
+    /// Appends the valid UTF-8 at the start of the internal buffer to `string`.
+    ///
+    /// Does nothing if the next character needs more than validation, such as when a byte order
+    /// mark may have to be stripped or `classify` is set.
+    pub(crate) fn read_valid_chunk(&mut self, string: &mut String) {
+        if !self.started || !self.pending.is_empty() || self.classify.is_some() {
+            return;
+        }
+
+        let available = &self.buffer[self.buffer_position..];
+        let valid = match simdutf8::compat::from_utf8(available) {
+            Ok(valid) => valid,
+            Err(error) => {
+                std::str::from_utf8(&available[..error.valid_up_to()]).unwrap_or_default()
+            }
+        };
+
+        let Some(last) = valid.chars().next_back() else {
+            return;
+        };
+
+        string.push_str(valid);
+        self.chars_decoded += valid.chars().count();
+        self.buffer_position += valid.len();
+        self.bytes_read += valid.len();
+        self.last_char_span = Some(self.bytes_read - last.len_utf8()..self.bytes_read);
+    }
+
+    /// Skip up to `amount` ASCII characters straight from the buffer, returning how many were skipped.
+    fn skip_ascii(&mut self, amount: usize) -> usize {
+        if !self.started || !self.pending.is_empty() || self.classify.is_some() {
+            return 0;
+        }
+
+        let skipped = self.buffer[self.buffer_position..]
+            .iter()
+            .take(amount)
+            .take_while(|byte| byte.is_ascii())
+            .count();
+        if skipped == 0 {
+            return 0;
+        }
+
+        self.chars_decoded += skipped;
+        self.buffer_position += skipped;
+        self.bytes_read += skipped;
+        self.last_char_span = Some(self.bytes_read - 1..self.bytes_read);
+
+        skipped
+    }
+
+    /// Reads a character along with the `len` bytes it was decoded from, without allocating.
+    fn read_char_raw(&mut self) -> Result<(char, [u8; 4], usize), CharacterError> {
+        let (mut character, mut bytes, mut len) = self.decode_char()?;
+        let is_first = !self.started;
+        self.started = true;
+
+        if is_first && self.strip_bom && character == '\u{FEFF}' {
+            (character, bytes, len) = self.decode_char()?;
+        }
+        debug_assert!((1..=4).contains(&len));
+
+        self.chars_decoded += 1;
+        if character == '\u{FFFD}' && bytes[..len] != *"\u{FFFD}".as_bytes() {
+            self.replacements += 1;
+        }
+
+        self.last_char_span = Some(self.bytes_read - len..self.bytes_read);
+
+        Ok((character, bytes, len))
+    }
+
+    /// The amount of characters read so far.
+    pub fn chars_read(&self) -> usize {
+        self.chars_decoded
+    }
+
+    /// The byte offset of the next byte that will be read.
+    pub fn byte_offset(&self) -> usize {
+        self.bytes_read
+    }
+
+    /// The start and end byte offsets of the most recently decoded character.
+    ///
+    /// Returns `None` if no character has been decoded yet.
+    pub fn last_char_span(&self) -> Option<Range<usize>> {
+        self.last_char_span.clone()
+    }
+
+    /// Snapshot of what the stream has done so far.
+    pub fn stats(&self) -> StreamStats {
+        debug_assert!(self.chars_decoded <= self.bytes_read);
+        debug_assert!(self.replacements <= self.chars_decoded);
+
+        StreamStats {
+            bytes_read: self.bytes_read,
+            chars_decoded: self.chars_decoded,
+            replacements: self.replacements,
+            interrupted_retries: 0,
+        }
+    }
+
+    /// Read from the internal buffer, refilling it from the stream once it is empty.
+    fn read_buffered(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.buffer_position == self.buffer.len() {
+            if buf.len() >= self.buffer_capacity {
+                return self.stream.read(buf);
+            }
+
+            self.buffer.resize(self.buffer_capacity, 0);
+            self.buffer_position = 0;
+            let result = self.stream.read(&mut self.buffer);
+            self.buffer.truncate(*result.as_ref().unwrap_or(&0));
+
+            // Validate the whole block at once, an incomplete character at the end of the block is
+            // validated byte by byte instead.
+            #[cfg(feature = "simd")]
+            {
+                self.validated_until = match simdutf8::compat::from_utf8(&self.buffer) {
+                    Ok(_) => self.buffer.len(),
+                    Err(error) => error.valid_up_to(),
+                };
+            }
+
+            result?;
+        }
+
+        let available = &self.buffer[self.buffer_position..];
+        let amount = available.len().min(buf.len());
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.buffer_position += amount;
+
+        Ok(amount)
+    }
+
+    /// In non-blocking mode, keep `bytes` when `error` is a [WouldBlock](io::ErrorKind::WouldBlock),
+    /// so the next read resumes the same character.
+    fn would_block(&mut self, mut bytes: Vec<u8>, error: CharacterError) -> CharacterError {
+        match error {
+            CharacterError::IoError { bytes: read, error }
+                if self.nonblocking && error.kind() == io::ErrorKind::WouldBlock =>
+            {
+                bytes.extend(read);
+                self.pending = bytes;
+                CharacterError::WouldBlock
+            }
+            error => error,
+        }
+    }
+
+    /// Reads a single byte, returning `Ok(None)` at the end of the stream.
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0u8];
+        if self.read_buffered(&mut byte)? == 0 {
+            return Ok(None);
+        }
+
+        self.bytes_read += 1;
+        Ok(Some(byte[0]))
+    }
+
+    /// Takes the next character from the buffer if it is ASCII, skipping any validation.
+    #[inline]
+    fn decode_ascii(&mut self) -> Option<(char, [u8; 4], usize)> {
+        let byte = *self.buffer.get(self.buffer_position)?;
+        if !byte.is_ascii() {
+            return None;
+        }
+
+        self.buffer_position += 1;
+        self.bytes_read += 1;
+
+        Some((byte as char, [byte, 0, 0, 0], 1))
+    }
+
+    /// Decodes the next character from the part of the buffer that was already validated.
+    #[cfg(feature = "simd")]
+    fn decode_validated(&mut self) -> Option<(char, [u8; 4], usize)> {
+        let valid = self
+            .buffer
+            .get(self.buffer_position..self.validated_until)?;
+        let len = remaining_byte_count(*valid.first()?)? + 1;
+        let mut bytes = [0u8; 4];
+        bytes[..len].copy_from_slice(&valid[..len]);
+
+        let mask = if len == 1 { 0x7F } else { 0x7F >> len };
+        let code_point = bytes[1..len]
+            .iter()
+            .fold((bytes[0] & mask) as u32, |code_point, byte| {
+                (code_point << 6) | (byte & 0x3F) as u32
+            });
+
+        self.buffer_position += len;
+        self.bytes_read += len;
+
+        Some((char::from_u32(code_point)?, bytes, len))
+    }
+
+    /// Run `classify` on a decoded character.
+    fn classified(
+        &self,
+        character: char,
+        bytes: [u8; 4],
+        len: usize,
+    ) -> Result<(char, [u8; 4], usize), CharacterError> {
+        match self.classify.and_then(|classify| classify(character)) {
+            Some(reason) => Err(CharacterError::Rejected {
+                bytes: bytes[..len].to_vec(),
+                character,
+                reason,
+            }),
+            None => Ok((character, bytes, len)),
+        }
+    }
+
+    fn decode_char(&mut self) -> Result<(char, [u8; 4], usize), CharacterError> {
+        if self.pending.is_empty() {
+            if let Some((character, bytes, len)) = self.decode_ascii() {
+                return self.classified(character, bytes, len);
+            }
+
+            #[cfg(feature = "simd")]
+            if let Some((character, bytes, len)) = self.decode_validated() {
+                return self.classified(character, bytes, len);
+            }
+        }
+
+        let mut bytes = [0u8; 4];
+        let mut len = self.pending.len();
+        bytes[..len].copy_from_slice(&self.pending);
+        self.pending.clear();
+
+        let decoder = self.decoder();
+        loop {
+            match decoder.decode(&bytes[..len]) {
+                Decoded::Char(character, used) => {
+                    self.unread_bytes(&bytes[used..len]);
+                    if self.surrogate_policy == SurrogatePolicy::PreserveAsReplacement
+                        && is_encoded_surrogate(&bytes[..used])
+                    {
+                        self.replaced_surrogates += 1;
+                    }
+
+                    return self.classified(character, bytes, used);
+                }
+                Decoded::Invalid(error, used) => {
+                    self.unread_bytes(&bytes[used..len]);
+                    return Err(error);
+                }
+                Decoded::Incomplete => {}
+            }
+
+            match self.next_byte() {
+                Ok(Some(byte)) => {
+                    bytes[len] = byte;
+                    len += 1;
+                }
+                Ok(None) if len == 0 => return Err(CharacterError::NoBytesRead),
+                Ok(None) => {
+                    let character = decoder.decode_truncated(&bytes[..len])?;
+                    return Ok((character, bytes, len));
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted && len > 0 => continue,
+                Err(error) => {
+                    let error = CharacterError::IoError {
+                        bytes: vec![],
+                        error,
+                    };
+                    return Err(self.would_block(bytes[..len].to_vec(), error));
+                }
+            }
+        }
+    }
+
+    /// The [Utf8Decoder] with the settings of this stream.
+    fn decoder(&self) -> Utf8Decoder {
+        Utf8Decoder::new(self.is_lossy)
+            .strict(self.strict)
+            .surrogate_policy(self.surrogate_policy)
+    }
+}
+
+impl<Reader: Read + Seek> CharacterStream<Reader> {
+    /// Seek to the end of the stream and back to find out how many bytes are left to decode.
+    ///
+    /// Afterwards, [CharacterIterator] can give a [size hint](Iterator::size_hint).
+    pub fn measure_remaining(&mut self) -> io::Result<usize> {
+        let position = self.stream.stream_position()?;
+        let end = self.stream.seek(SeekFrom::End(0))?;
+        self.stream.seek(SeekFrom::Start(position))?;
+
+        let buffered = self.buffer.len() - self.buffer_position;
+        let remaining = (end.saturating_sub(position) as usize) + buffered;
+        self.byte_length = Some(self.bytes_read + remaining);
+
+        Ok(remaining)
+    }
+}
+
+impl<T: AsRef<[u8]>> CharacterStream<Cursor<T>> {
+    /// Rewind the stream to the start of the in-memory source, so it can be decoded again.
+    ///
+    /// The counters reported by [stats](CharacterStream::stats) are cleared as well.
+    pub fn reset(&mut self) {
+        self.stream.set_position(0);
+        self.replaced_surrogates = 0;
+        self.bytes_read = 0;
+        self.chars_decoded = 0;
+        self.replacements = 0;
+        self.started = false;
+        self.last_char_span = None;
+        self.pending.clear();
+        self.buffer.clear();
+        self.buffer_position = 0;
+        #[cfg(feature = "simd")]
+        {
+            self.validated_until = 0;
+        }
+    }
+}
+
+/// [Read] implementation over the internal buffer of a [CharacterStream].
+struct Buffered<'a, Reader: Read>(&'a mut CharacterStream<Reader>);
+
+impl<Reader: Read> Read for Buffered<'_, Reader> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read_buffered(buf)
+    }
+}
+
+impl<Reader: Read> CharStream for CharacterStream<Reader> {
+    /// Attempts to read a character from the stream.
+    ///
+    /// If `is_lossy` is set to `true`, then invalid byte sequences will be a U+FFFD.
+    ///
+    /// If `is_lossy` is set to `false`, then invalid byte sequences will be returned in addition to a parse error.
+    fn read_char(&mut self) -> CharacterStreamResult {
+        self.read_char_raw().map(|(character, ..)| character)
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+
+    fn bytes_read(&self) -> Option<usize> {
+        Some(self.bytes_read)
+    }
+
+    fn bytes_remaining(&self) -> Option<usize> {
+        self.byte_length
+            .map(|byte_length| byte_length.saturating_sub(self.bytes_read))
+    }
+}
+
+impl<Reader: std::fmt::Debug + Read> std::fmt::Debug for CharacterStream<Reader> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CharacterStream")
+            .field("stream", &self.stream)
+            .field("is_lossy", &self.is_lossy)
+            .field("classify", &self.classify)
+            .field("surrogate_policy", &self.surrogate_policy)
+            .field("replaced_surrogates", &self.replaced_surrogates)
+            .field("strip_bom", &self.strip_bom)
+            .field("strict", &self.strict)
+            .field("nonblocking", &self.nonblocking)
+            .finish()
+    }
+}
+
+impl<Reader: Read> From<Reader> for CharacterStream<Reader> {
+    fn from(reader: Reader) -> Self {
+        Self::new(reader, false)
+    }
+}
+
+/// The amount of peeked characters a [PeekableCharacterStream] can hold before allocating again.
+const PEEK_BUFFER_CAPACITY: usize = 8;
+
+/// Wrapper that adds peeking to a [CharStream].
+///
+/// By default it wraps a [CharacterStream], but any other [CharStream] reading from `Reader` may be used.
+pub struct PeekableCharacterStream<Reader: Read, PI, Stream: CharStream = CharacterStream<Reader>> {
+    pub stream: Stream,
+    /// The peeked characters.
+    ///
+    /// This is a ring buffer that is allocated up front, so peeking and reading do not allocate
+    /// or move characters unless the buffer has to grow past its capacity.
+    pub buffer: VecDeque<CharacterStreamResult>,
+    pub position: usize,
+    /// Maximum amount of characters the peek buffer may hold.
+    ///
+    /// If `None`, then the buffer may grow without bound.
+    pub max_peek_buffer: Option<usize>,
+    /// Holds the error returned once the peek buffer limit is exceeded.
+    overflow: Option<CharacterStreamResult>,
+    /// The amount of characters read, not counting peeked characters.
+    pub(crate) chars_read: usize,
+    /// The amount of results read, including errors.
+    pub(crate) consumed: usize,
+    /// The value of `consumed` when recording for a [Checkpoint](crate::Checkpoint) started.
+    pub(crate) recorded_from: Option<usize>,
+    /// The results read since `recorded_from`, so they can be replayed.
+    pub(crate) recorded: Vec<CharacterStreamResult>,
+    /// The open savepoints, innermost last.
+    pub(crate) savepoints: Vec<Checkpoint>,
+    _phantom: PhantomData<(PI, fn() -> Reader)>,
+}
+
+impl<Reader: Read, PI> PeekableCharacterStream<Reader, PI> {
+    pub fn new(stream: Reader, is_lossy: bool) -> Self {
+        Self::from_stream(CharacterStream::new(stream, is_lossy))
+    }
+}
+
+impl<Reader: Read, PI, Stream: CharStream> PeekableCharacterStream<Reader, PI, Stream> {
+    pub fn from_stream(stream: Stream) -> Self {
+        Self {
+            stream,
+            buffer: VecDeque::with_capacity(PEEK_BUFFER_CAPACITY),
             position: 0,
+            max_peek_buffer: None,
+            overflow: None,
+            chars_read: 0,
+            consumed: 0,
+            recorded_from: None,
+            recorded: vec![],
+            savepoints: vec![],
             _phantom: PhantomData,
         }
     }
 
-    #[inline]
-    fn _read_char(&mut self) -> CharacterStreamResult {
+    /// Kinda builder pattern.
+    ///
+    /// Limit the peek buffer to `max` characters.
+    pub fn with_max_peek(mut self, max: usize) -> Self {
+        self.max_peek_buffer = Some(max);
         self.buffer
+            .reserve_exact(max.saturating_sub(self.buffer.len()));
+        self
+    }
+
+    /// The amount of characters read so far, not counting peeked characters.
+    pub fn chars_read(&self) -> usize {
+        self.chars_read
+    }
+
+    /// Push `character` back, so it is returned by the next read.
+    ///
+    /// The peek position is reset.
+    pub fn unread(&mut self, character: char) {
+        self.unread_result(Ok(character));
+    }
+
+    /// Push `result` back, so it is returned by the next read.
+    ///
+    /// The peek position is reset.
+    pub fn unread_result(&mut self, result: CharacterStreamResult) {
+        if result.is_ok() {
+            self.chars_read = self.chars_read.saturating_sub(1);
+        }
+
+        if !matches!(result, Err(CharacterError::NoBytesRead)) {
+            if self.recorded_from.is_some_and(|from| self.consumed > from) {
+                self.recorded.pop();
+            }
+            self.consumed = self.consumed.saturating_sub(1);
+        }
+
+        self.position = 0;
+        self.buffer.push_front(result);
+    }
+
+    /// Push `string` back, so its characters are returned by the next reads, in order.
+    ///
+    /// Pushing back several strings is last in, first out. The peek position is reset.
+    pub fn unread_str(&mut self, string: &str) {
+        self.unread_chars(string.chars());
+    }
+
+    /// Push `characters` back, so they are returned by the next reads, in order.
+    ///
+    /// Pushing back several sequences is last in, first out. The peek position is reset.
+    pub fn unread_chars<I>(&mut self, characters: I)
+    where
+        I: IntoIterator<Item = char>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        for character in characters.into_iter().rev() {
+            self.unread(character);
+        }
+    }
+
+    /// Peek the character `n` positions ahead, where 0 is the next character.
+    ///
+    /// Unlike [MultiPeekable::peek], the peek position is left untouched.
+    ///
+    /// Returns `None` if the stream ends before that character.
+    pub fn peek_nth(&mut self, n: usize) -> Option<&CharacterStreamResult> {
+        while self.buffer.len() <= n {
+            if matches!(self.buffer.back(), Some(Err(CharacterError::NoBytesRead))) {
+                return None;
+            }
+
+            if let Some(max) = self.max_peek_buffer {
+                if self.buffer.len() >= max {
+                    self.overflow = Some(Err(CharacterError::PeekLimitExceeded(max)));
+                    return self.overflow.as_ref();
+                }
+            }
+
+            match self.stream.read_char() {
+                Err(CharacterError::NoBytesRead) => return None,
+                result => self.buffer.push_back(result),
+            }
+        }
+
+        match &self.buffer[n] {
+            Err(CharacterError::NoBytesRead) => None,
+            result => Some(result),
+        }
+    }
+
+    /// Fill `out` with the upcoming characters, without consuming them.
+    ///
+    /// Slots past the end of the stream or past an error are set to `None`, the error stays in the
+    /// peek buffer for [read_char](CharStream::read_char) to return. The peek position is left
+    /// untouched.
+    ///
+    /// Returns the amount of characters that were filled in.
+    pub fn peek_many(&mut self, out: &mut [Option<char>]) -> usize {
+        let mut filled = 0;
+
+        while filled < out.len() {
+            match self.peek_nth(filled) {
+                Some(Ok(character)) => out[filled] = Some(*character),
+                _ => break,
+            }
+
+            filled += 1;
+        }
+
+        out[filled..].fill(None);
+        filled
+    }
+
+    /// Returns the underlying stream along with the peeked results that haven't been read yet,
+    /// in the order they would have been read.
+    pub fn into_inner(self) -> (Stream, Vec<CharacterStreamResult>) {
+        (self.stream, self.buffer.into())
+    }
+
+    /// Peek the next `n` characters as a string, without consuming them.
+    ///
+    /// The string is shorter than `n` characters if the stream ends or an error comes up first.
+    /// The peek position is left untouched.
+    pub fn peek_str(&mut self, n: usize) -> String {
+        let mut peeked = String::with_capacity(n);
+
+        for i in 0..n {
+            match self.peek_nth(i) {
+                Some(Ok(character)) => peeked.push(*character),
+                _ => break,
+            }
+        }
+
+        peeked
+    }
+
+    /// Consume the next character if it satisfies `predicate`, like [std::iter::Peekable::next_if].
+    ///
+    /// If the next item is an error, it is consumed and returned. When anything is consumed, the
+    /// peek position is reset.
+    pub fn next_if(
+        &mut self,
+        predicate: impl FnOnce(&char) -> bool,
+    ) -> Option<CharacterStreamResult> {
+        match self.peek_nth(0) {
+            Some(Ok(character)) if predicate(character) => {}
+            Some(Err(_)) => {}
+            _ => return None,
+        }
+
+        self.position = 0;
+        Some(self._read_char())
+    }
+
+    /// Consume the next character if it is equal to `expected`, like
+    /// [std::iter::Peekable::next_if_eq].
+    pub fn next_if_eq(&mut self, expected: char) -> Option<CharacterStreamResult> {
+        self.next_if(|character| *character == expected)
+    }
+
+    #[inline]
+    pub(crate) fn _read_char(&mut self) -> CharacterStreamResult {
+        let result = self
+            .buffer
             .pop_front()
-            .unwrap_or_else(|| self.stream.read_char())
+            .unwrap_or_else(|| self.stream.read_char());
+
+        if result.is_ok() {
+            self.chars_read += 1;
+        }
+
+        if !matches!(result, Err(CharacterError::NoBytesRead)) {
+            self.consumed += 1;
+            if self.recorded_from.is_some() {
+                let copy = result.as_ref().map(|character| *character);
+                self.recorded.push(copy.map_err(CharacterError::duplicate));
+            }
+        }
+
+        result
+    }
+}
+
+impl<Reader: Read, Stream: CharStream> PeekableCharacterStream<Reader, MultiPeek, Stream> {
+    /// The current multi-peek position, i.e. how many characters [peek](MultiPeekable::peek)
+    /// has gone past since the last reset.
+    pub fn peek_position(&self) -> usize {
+        self.position
+    }
+
+    /// Move the multi-peek position, e.g. back to one recorded with [peek_position](PeekableCharacterStream::peek_position).
+    ///
+    /// The position is clamped to the amount of characters already peeked.
+    pub fn set_peek_position(&mut self, position: usize) {
+        self.position = position.min(self.buffer.len());
+    }
+
+    /// Peek the upcoming characters while they satisfy `predicate`, without consuming them.
+    ///
+    /// Peeking stops at the end of the stream or at an error. The peek position is left untouched.
+    pub fn peek_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> String {
+        let mut peeked = String::new();
+        let mut n = 0;
+
+        while let Some(Ok(character)) = self.peek_nth(n) {
+            if !predicate(*character) {
+                break;
+            }
+            peeked.push(*character);
+            n += 1;
+        }
+
+        peeked
+    }
+}
+
+impl<T: AsRef<[u8]>, PI> PeekableCharacterStream<Cursor<T>, PI> {
+    /// Rewind the stream to the start of the in-memory source, so it can be decoded again.
+    ///
+    /// The peek buffer and peek position are cleared as well.
+    pub fn reset(&mut self) {
+        self.stream.reset();
+        self.buffer.clear();
+        self.position = 0;
+        self.overflow = None;
+        self.chars_read = 0;
+        self.consumed = 0;
+        self.recorded_from = None;
+        self.recorded.clear();
+        self.savepoints.clear();
     }
 }
 
@@ -269,23 +1545,34 @@ impl<Reader: Read, PI> From<CharacterStream<Reader>> for PeekableCharacterStream
     }
 }
 
-impl<Reader: Read> Peekable<CharacterStreamResult> for PeekableCharacterStream<Reader, Peek> {
+impl<Reader: Read, Stream: CharStream> Peekable<CharacterStreamResult>
+    for PeekableCharacterStream<Reader, Peek, Stream>
+{
     fn peek(&mut self) -> Option<&CharacterStreamResult> {
-        if self.buffer.len() == 1 {
+        if !self.buffer.is_empty() {
             return self.buffer.front();
         }
 
-        let character_result = self.read_char();
+        let character_result = self.stream.read_char();
         self.buffer.push_back(character_result);
 
         self.buffer.front()
     }
 }
 
-impl<Reader: Read> MultiPeekable<CharacterStreamResult>
-    for PeekableCharacterStream<Reader, MultiPeek>
+impl<Reader: Read, Stream: CharStream> MultiPeekable<CharacterStreamResult>
+    for PeekableCharacterStream<Reader, MultiPeek, Stream>
 {
     fn peek(&mut self) -> Option<&CharacterStreamResult> {
+        if self.position >= self.buffer.len() {
+            if let Some(max) = self.max_peek_buffer {
+                if self.buffer.len() >= max {
+                    self.overflow = Some(Err(CharacterError::PeekLimitExceeded(max)));
+                    return self.overflow.as_ref();
+                }
+            }
+        }
+
         let ret = if self.position < self.buffer.len() {
             Some(&self.buffer[self.position])
         } else {
@@ -307,24 +1594,44 @@ impl<Reader: Read> MultiPeekable<CharacterStreamResult>
     }
 }
 
-impl<Reader: Read> CharStream for PeekableCharacterStream<Reader, Peek> {
+impl<Reader: Read, Stream: CharStream> CharStream
+    for PeekableCharacterStream<Reader, Peek, Stream>
+{
     fn read_char(&mut self) -> CharacterStreamResult {
         self._read_char()
     }
 
     fn is_lossy(&self) -> bool {
-        self.stream.is_lossy
+        self.stream.is_lossy()
     }
 }
 
-impl<Reader: Read> CharStream for PeekableCharacterStream<Reader, MultiPeek> {
+impl<Reader: Read, Stream: CharStream> CharStream
+    for PeekableCharacterStream<Reader, MultiPeek, Stream>
+{
     fn read_char(&mut self) -> CharacterStreamResult {
         self.reset_peek();
         self._read_char()
     }
 
     fn is_lossy(&self) -> bool {
-        self.stream.is_lossy
+        self.stream.is_lossy()
+    }
+}
+
+impl<Reader: Read, Stream: CharStream> PeekCharStream
+    for PeekableCharacterStream<Reader, Peek, Stream>
+{
+    fn peek_nth(&mut self, n: usize) -> Option<&CharacterStreamResult> {
+        PeekableCharacterStream::peek_nth(self, n)
+    }
+}
+
+impl<Reader: Read, Stream: CharStream> PeekCharStream
+    for PeekableCharacterStream<Reader, MultiPeek, Stream>
+{
+    fn peek_nth(&mut self, n: usize) -> Option<&CharacterStreamResult> {
+        PeekableCharacterStream::peek_nth(self, n)
     }
 }
 
@@ -333,17 +1640,59 @@ pub trait ToCharacterStream<Reader: Read> {
     /// Convert into a [CharacterStream].
     fn to_character_stream(&self) -> CharacterStream<Reader>;
 
-    /// Convert into a lossy [CharacterStream].
-    fn to_character_stream_lossy(&self) -> CharacterStream<Reader>;
+    /// Convert into a lossy [CharacterStream].
+    fn to_character_stream_lossy(&self) -> CharacterStream<Reader>;
+}
+
+impl<T: AsRef<[u8]>> ToCharacterStream<Cursor<Vec<u8>>> for T {
+    fn to_character_stream(&self) -> CharacterStream<Cursor<Vec<u8>>> {
+        self.as_ref().to_vec().into_character_stream()
+    }
+
+    fn to_character_stream_lossy(&self) -> CharacterStream<Cursor<Vec<u8>>> {
+        self.as_ref().to_vec().into_character_stream_lossy()
+    }
+}
+
+/// Helper trait for converting owned values into a [CharacterStream] without copying them.
+pub trait IntoCharacterStream<Reader: Read> {
+    /// Convert into a [CharacterStream].
+    fn into_character_stream(self) -> CharacterStream<Reader>;
+
+    /// Convert into a lossy [CharacterStream].
+    fn into_character_stream_lossy(self) -> CharacterStream<Reader>;
+}
+
+impl IntoCharacterStream<Cursor<Vec<u8>>> for Vec<u8> {
+    fn into_character_stream(self) -> CharacterStream<Cursor<Vec<u8>>> {
+        let byte_length = self.len();
+        CharacterStream::from(Cursor::new(self)).with_byte_length(byte_length)
+    }
+
+    fn into_character_stream_lossy(self) -> CharacterStream<Cursor<Vec<u8>>> {
+        let byte_length = self.len();
+        CharacterStream::new(Cursor::new(self), true).with_byte_length(byte_length)
+    }
+}
+
+/// Helper trait for reading borrowed values as a [CharacterStream] without copying them.
+pub trait AsCharacterStream {
+    /// Borrow as a [CharacterStream].
+    fn as_character_stream(&self) -> CharacterStream<&[u8]>;
+
+    /// Borrow as a lossy [CharacterStream].
+    fn as_character_stream_lossy(&self) -> CharacterStream<&[u8]>;
 }
 
-impl<T: AsRef<[u8]>> ToCharacterStream<Cursor<Vec<u8>>> for T {
-    fn to_character_stream(&self) -> CharacterStream<Cursor<Vec<u8>>> {
-        CharacterStream::from(Cursor::new(self.as_ref().to_vec()))
+impl<T: AsRef<[u8]> + ?Sized> AsCharacterStream for T {
+    fn as_character_stream(&self) -> CharacterStream<&[u8]> {
+        let bytes = self.as_ref();
+        CharacterStream::from(bytes).with_byte_length(bytes.len())
     }
 
-    fn to_character_stream_lossy(&self) -> CharacterStream<Cursor<Vec<u8>>> {
-        CharacterStream::new(Cursor::new(self.as_ref().to_vec()), true)
+    fn as_character_stream_lossy(&self) -> CharacterStream<&[u8]> {
+        let bytes = self.as_ref();
+        CharacterStream::new(bytes, true).with_byte_length(bytes.len())
     }
 }
 
@@ -380,7 +1729,9 @@ impl<Reader: Read> IntoIterator for CharacterStream<Reader> {
     }
 }
 
-impl<Reader: Read> IntoIterator for PeekableCharacterStream<Reader, Peek> {
+impl<Reader: Read, Stream: CharStream> IntoIterator
+    for PeekableCharacterStream<Reader, Peek, Stream>
+{
     type Item = <Self::IntoIter as Iterator>::Item;
 
     type IntoIter = CharacterIterator<Self>;
@@ -390,7 +1741,9 @@ impl<Reader: Read> IntoIterator for PeekableCharacterStream<Reader, Peek> {
     }
 }
 
-impl<Reader: Read> IntoIterator for PeekableCharacterStream<Reader, MultiPeek> {
+impl<Reader: Read, Stream: CharStream> IntoIterator
+    for PeekableCharacterStream<Reader, MultiPeek, Stream>
+{
     type Item = <Self::IntoIter as Iterator>::Item;
 
     type IntoIter = CharacterIterator<Self>;
@@ -435,4 +1788,822 @@ mod tests {
 
         println!();
     }
+
+    #[test]
+    fn max_peek_test() {
+        let mut character_stream = b"abcdef"
+            .to_character_stream()
+            .peeky_multi()
+            .with_max_peek(3);
+
+        for expected in ['a', 'b', 'c'] {
+            assert_eq!(
+                character_stream.peek().unwrap().as_ref().unwrap(),
+                &expected
+            );
+        }
+
+        assert!(matches!(
+            character_stream.peek(),
+            Some(Err(CharacterError::PeekLimitExceeded(3)))
+        ));
+
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        character_stream.reset_peek();
+
+        for expected in ['b', 'c', 'd'] {
+            assert_eq!(
+                character_stream.peek().unwrap().as_ref().unwrap(),
+                &expected
+            );
+        }
+    }
+
+    #[test]
+    fn read_char_or_eof_test() {
+        let mut character_stream = "ab".to_character_stream();
+        let mut read = String::new();
+
+        while let Some(c) = character_stream.read_char_or_eof().unwrap() {
+            read.push(c);
+        }
+
+        assert_eq!(read, "ab");
+
+        let mut character_stream = b"a\xF0\x9F".to_character_stream();
+        assert_eq!(character_stream.read_char_or_eof().unwrap(), Some('a'));
+        assert!(character_stream.read_char_or_eof().is_err());
+
+        let mut character_stream = b"a\xF0".to_character_stream();
+        assert_eq!(character_stream.read_char_or_eof().unwrap(), Some('a'));
+        assert!(character_stream.read_char_or_eof().is_err());
+    }
+
+    #[test]
+    fn into_character_stream_test() {
+        let bytes = "a".repeat(1 << 20).into_bytes();
+        let pointer = bytes.as_ptr();
+        let capacity = bytes.capacity();
+
+        let mut character_stream = bytes.into_character_stream();
+        assert_eq!(character_stream.get_ref().get_ref().as_ptr(), pointer);
+        assert_eq!(character_stream.get_ref().get_ref().capacity(), capacity);
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+    }
+
+    #[test]
+    fn utf8_error_test() {
+        let mut character_stream = b"\xE0\x80\x80\xE2\x28\xA1".to_character_stream();
+
+        match character_stream.read_char() {
+            Err(CharacterError::Utf8 {
+                bytes,
+                valid_up_to,
+                error_len,
+            }) => {
+                assert_eq!(bytes, b"\xE0\x80\x80");
+                assert_eq!(valid_up_to, 0);
+                assert_eq!(error_len, Some(1));
+            }
+            other => panic!("Expected a UTF-8 error, got {:?}", other),
+        }
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::InvalidSequence(bytes)) if bytes == b"\xE2"
+        ));
+        assert_eq!(character_stream.read_char().unwrap(), '(');
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::UnexpectedContinuation(0xA1))
+        ));
+        assert_eq!(character_stream.byte_offset(), 6);
+    }
+
+    #[test]
+    fn reset_test() {
+        let mut character_stream = "a\u{1F4BB}b".to_character_stream().peeky_multi();
+        let decode = |character_stream: &mut PeekableCharacterStream<_, MultiPeek>| {
+            character_stream.peek();
+            let mut read = String::new();
+
+            while let Some(c) = character_stream.read_char_or_eof().unwrap() {
+                read.push(c);
+            }
+
+            read
+        };
+
+        let first = decode(&mut character_stream);
+        character_stream.peek();
+        character_stream.reset();
+        assert_eq!(character_stream.position, 0);
+        assert!(character_stream.buffer.is_empty());
+        assert_eq!(decode(&mut character_stream), first);
+    }
+
+    #[test]
+    fn classify_test() {
+        let mut character_stream = "a\u{85}b".to_character_stream().classify(|c| match c {
+            '\u{85}' => Some("next line characters are not allowed"),
+            _ => None,
+        });
+
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        let error = character_stream.read_char().unwrap_err();
+        assert_eq!(error.bytes(), Some("\u{85}".as_bytes()));
+        assert!(matches!(
+            error,
+            CharacterError::Rejected {
+                character: '\u{85}',
+                reason: "next line characters are not allowed",
+                ..
+            }
+        ));
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+    }
+
+    #[test]
+    fn surrogate_policy_test() {
+        // U+1F4BB encoded as a CESU-8 surrogate pair.
+        let bytes = b"\xED\xA0\xBD\xED\xB2\xBBa";
+
+        let mut character_stream = bytes.to_character_stream();
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::Utf8 { .. })
+        ));
+
+        let mut character_stream = bytes
+            .to_character_stream()
+            .surrogate_policy(SurrogatePolicy::Replace);
+        let read: String = character_stream
+            .take_chars(3)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, "\u{FFFD}\u{FFFD}a");
+        assert_eq!(character_stream.replaced_surrogates, 0);
+
+        let mut character_stream = bytes
+            .to_character_stream()
+            .surrogate_policy(SurrogatePolicy::PreserveAsReplacement);
+        let read: String = character_stream
+            .take_chars(3)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, "\u{FFFD}\u{FFFD}a");
+        assert_eq!(character_stream.replaced_surrogates, 2);
+    }
+
+    #[test]
+    fn read_zero_bytes_test() {
+        let mut character_stream = "".to_character_stream();
+
+        assert_eq!(character_stream.read_bytes(0).unwrap(), Vec::<u8>::new());
+        assert!(matches!(
+            character_stream.read_bytes(1),
+            Err(CharacterError::NoBytesRead)
+        ));
+    }
+
+    #[test]
+    fn strip_bom_test() {
+        let read = |character_stream: CharacterStream<Cursor<Vec<u8>>>| -> String {
+            character_stream
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            read("\u{FEFF}a\u{FEFF}".to_character_stream().strip_bom(true)),
+            "a\u{FEFF}"
+        );
+        assert_eq!(read("\u{FEFF}a".to_character_stream()), "\u{FEFF}a");
+    }
+
+    #[test]
+    fn wtf8_test() {
+        let mut iter = b"a\xED\xA0\xBDb"
+            .to_character_stream()
+            .surrogate_policy(SurrogatePolicy::Wtf8)
+            .into_iter();
+
+        assert_eq!(iter.next().unwrap().unwrap(), 'a');
+        assert!(matches!(
+            iter.next(),
+            Some(Err(CharacterError::Surrogate {
+                code_point: 0xD83D,
+                ..
+            }))
+        ));
+        assert_eq!(iter.next().unwrap().unwrap(), 'b');
+    }
+
+    #[test]
+    fn strict_test() {
+        let read_error = |bytes: &[u8]| {
+            bytes
+                .to_character_stream()
+                .strict(true)
+                .read_char()
+                .unwrap_err()
+        };
+
+        assert!(matches!(
+            read_error(b"\xC0\xAF"),
+            CharacterError::Overlong { .. }
+        ));
+        assert!(matches!(
+            read_error(b"\xE0\x80\xAF"),
+            CharacterError::Overlong { .. }
+        ));
+        assert!(matches!(
+            read_error(b"\xED\xB2\xBB"),
+            CharacterError::Surrogate {
+                code_point: 0xDCBB,
+                ..
+            }
+        ));
+        assert!(matches!(
+            read_error(b"\xF4\x90\x80\x80"),
+            CharacterError::OutOfRange {
+                code_point: 0x110000,
+                ..
+            }
+        ));
+        assert!(matches!(
+            read_error(b"\xE2\x28\xA1"),
+            CharacterError::InvalidSequence(_)
+        ));
+    }
+
+    #[test]
+    fn byte_offset_test() {
+        let mut character_stream = "\u{FEFF}a\u{1F4BB}\u{E9}"
+            .to_character_stream()
+            .strip_bom(true);
+        assert_eq!(character_stream.last_char_span(), None);
+
+        let mut spans = vec![];
+        while character_stream.read_char_or_eof().unwrap().is_some() {
+            spans.push(character_stream.last_char_span().unwrap());
+        }
+
+        assert_eq!(spans, [3..4, 4..8, 8..10]);
+        assert_eq!(character_stream.byte_offset(), 10);
+    }
+
+    #[test]
+    fn chars_read_test() {
+        let mut character_stream = "abc".to_character_stream().peeky();
+        character_stream.peek();
+        assert_eq!(character_stream.chars_read(), 0);
+
+        character_stream.read_char().unwrap();
+        character_stream.peek();
+        assert_eq!(character_stream.chars_read(), 1);
+        assert_eq!(character_stream.stream.chars_read(), 2);
+
+        let mut iter = character_stream.into_iter();
+        iter.next();
+        assert_eq!(iter.chars_read(), 1);
+        assert_eq!(iter.stream().chars_read(), 2);
+    }
+
+    #[test]
+    fn nonblocking_test() {
+        /// Would block before every byte.
+        struct BlockingReader(Cursor<&'static [u8]>, bool);
+
+        impl Read for BlockingReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.1 = !self.1;
+                if self.1 {
+                    return Err(io::ErrorKind::WouldBlock.into());
+                }
+
+                self.0.read(&mut buf[..1])
+            }
+        }
+
+        let reader = BlockingReader(Cursor::new("a\u{1F4BB}".as_bytes()), false);
+        let mut character_stream = CharacterStream::from(reader).nonblocking(true);
+        let mut characters = String::new();
+
+        loop {
+            match character_stream.read_char() {
+                Ok(character) => characters.push(character),
+                Err(CharacterError::WouldBlock) => continue,
+                Err(CharacterError::NoBytesRead) => break,
+                Err(error) => panic!("{error}"),
+            }
+        }
+
+        assert_eq!(characters, "a\u{1F4BB}");
+        assert_eq!(character_stream.byte_offset(), 5);
+    }
+
+    #[test]
+    fn buffered_read_test() {
+        /// Counts the calls to `read`.
+        struct CountingReader(Cursor<Vec<u8>>, usize);
+
+        impl Read for CountingReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.1 += 1;
+                self.0.read(buf)
+            }
+        }
+
+        let text = "\u{1F4BB} abc ".repeat(1000);
+        let reader = CountingReader(Cursor::new(text.clone().into_bytes()), 0);
+        let mut character_stream = CharacterStream::from(reader);
+        let mut read = String::new();
+
+        while let Some(character) = character_stream.read_char_or_eof().unwrap() {
+            read.push(character);
+        }
+
+        assert_eq!(read, text);
+        assert_eq!(
+            character_stream.stream.1,
+            text.len().div_ceil(DEFAULT_BUFFER_CAPACITY) + 1
+        );
+    }
+
+    #[test]
+    fn truncated_char_test() {
+        let mut character_stream = b"a\xF0\x9F\x92".to_character_stream();
+
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::TruncatedSequence { bytes, expected: 4, got: 3 }) if bytes == [0xF0, 0x9F, 0x92]
+        ));
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::NoBytesRead)
+        ));
+    }
+
+    #[test]
+    fn block_boundary_test() {
+        let text = b"ab\xF0\x9F\x92\xBBc\xFF\xE2\x82\xACd\xC3\xA9";
+
+        for buffer_capacity in 1..=8 {
+            let mut character_stream = text
+                .to_character_stream_lossy()
+                .with_buffer_capacity(buffer_capacity);
+            let mut read = String::new();
+
+            while let Some(character) = character_stream.read_char_or_eof().unwrap() {
+                read.push(character);
+            }
+
+            assert_eq!(read, "ab\u{1F4BB}c\u{FFFD}\u{20AC}d\u{E9}");
+            assert_eq!(character_stream.byte_offset(), text.len());
+        }
+    }
+
+    #[test]
+    fn as_character_stream_test() {
+        let text = String::from("borrowed \u{1F4BB}");
+        let read: String = text
+            .as_character_stream()
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(read, text);
+
+        let bytes: &[u8] = b"a\xFFb";
+        let mut character_stream = bytes.as_character_stream_lossy();
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert_eq!(character_stream.read_char().unwrap(), '\u{FFFD}');
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+    }
+
+    #[test]
+    fn ascii_fast_path_test() {
+        let text = "plain ascii, then \u{E9}, then ascii again";
+        let mut character_stream = text
+            .as_character_stream()
+            .classify(|character| (character == 'X').then_some("no X"));
+        let mut read = String::new();
+
+        while let Some(character) = character_stream.read_char_or_eof().unwrap() {
+            assert_eq!(
+                character_stream.last_char_span(),
+                Some(read.len()..read.len() + character.len_utf8())
+            );
+            read.push(character);
+        }
+
+        assert_eq!(read, text);
+        let mut character_stream = "aXb"
+            .as_character_stream()
+            .classify(|character| (character == 'X').then_some("no X"));
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert!(character_stream.read_char().is_err());
+    }
+
+    #[test]
+    fn read_chars_test() {
+        let mut character_stream = "ab\u{1F4BB}cdef".as_character_stream();
+
+        assert_eq!(character_stream.read_chars(3).unwrap(), "ab\u{1F4BB}");
+        assert_eq!(character_stream.read_chars(0).unwrap(), "");
+        assert_eq!(character_stream.read_chars(10).unwrap(), "cdef");
+        assert_eq!(character_stream.read_chars(1).unwrap(), "");
+    }
+
+    #[test]
+    fn read_to_string_test() {
+        let text = "\u{FEFF}first line\nsecond \u{1F4BB} line\n".repeat(500);
+        let mut character_stream = text.as_character_stream().strip_bom(true);
+        let read = character_stream.read_to_string().unwrap();
+
+        assert_eq!(read, text[3..]);
+        assert_eq!(character_stream.chars_read(), read.chars().count());
+        assert_eq!(character_stream.byte_offset(), text.len());
+
+        let mut character_stream = b"ok\xFFrest".as_character_stream();
+        let (partial, error) = character_stream.read_to_string().unwrap_err();
+        assert_eq!(partial, "ok");
+        assert_eq!(error.bytes(), Some(&[0xFF][..]));
+
+        let mut character_stream = b"ok\xFFrest".as_character_stream();
+        assert_eq!(
+            character_stream.read_to_string_lossy().unwrap(),
+            "ok\u{FFFD}rest"
+        );
+        assert!(!character_stream.is_lossy);
+    }
+
+    #[test]
+    fn peek_buffer_capacity_test() {
+        let text = "lexers peek on nearly every token ".repeat(100);
+        let mut character_stream = text.to_character_stream().peeky_multi().with_max_peek(16);
+        let capacity = character_stream.buffer.capacity();
+        assert!(capacity >= 16);
+
+        loop {
+            character_stream.peek();
+            character_stream.peek();
+            if character_stream.read_char().is_err() {
+                break;
+            }
+        }
+
+        assert_eq!(character_stream.buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn buffer_capacity_test() {
+        /// Records the size of every read.
+        struct RecordingReader(Cursor<&'static [u8]>, Vec<usize>);
+
+        impl Read for RecordingReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.1.push(buf.len());
+                self.0.read(buf)
+            }
+        }
+
+        let reader = RecordingReader(Cursor::new(b"abc"), vec![]);
+        let mut character_stream = CharacterStream::from(reader).with_buffer_capacity(2);
+        assert_eq!(character_stream.buffer_capacity(), 2);
+        assert_eq!(character_stream.read_chars(3).unwrap(), "abc");
+        assert_eq!(character_stream.stream.1, [2, 2]);
+
+        let reader = RecordingReader(Cursor::new(b"ab"), vec![]);
+        let mut character_stream = CharacterStream::from(reader).with_buffer_capacity(0);
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert_eq!(character_stream.stream.0.position(), 1);
+    }
+
+    #[test]
+    fn short_read_test() {
+        /// Returns at most two bytes per read.
+        struct ShortReader(Cursor<&'static [u8]>);
+
+        impl Read for ShortReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = buf.len().min(2);
+                self.0.read(&mut buf[..len])
+            }
+        }
+
+        let reader = ShortReader(Cursor::new("\u{1F4BB}abcdef".as_bytes()));
+        let mut character_stream = CharacterStream::from(reader).with_buffer_capacity(1);
+
+        assert_eq!(
+            character_stream.read_bytes(4).unwrap(),
+            "\u{1F4BB}".as_bytes()
+        );
+
+        let mut buf = [0u8; 5];
+        assert_eq!(character_stream.read_bytes_into(&mut buf).unwrap(), 5);
+        assert_eq!(&buf, b"abcde");
+        assert_eq!(character_stream.read_bytes_into(&mut buf).unwrap(), 1);
+        assert_eq!(character_stream.byte_offset(), 10);
+    }
+
+    #[test]
+    fn peek_nth_test() {
+        let mut character_stream = "abc".to_character_stream().peeky();
+        assert_eq!(
+            character_stream.peek_nth(2).unwrap().as_ref().unwrap(),
+            &'c'
+        );
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'a');
+        assert!(character_stream.peek_nth(3).is_none());
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert_eq!(
+            character_stream.peek_nth(0).unwrap().as_ref().unwrap(),
+            &'b'
+        );
+
+        let mut character_stream = "abc".to_character_stream().peeky_multi();
+        character_stream.peek();
+        assert_eq!(
+            character_stream.peek_nth(1).unwrap().as_ref().unwrap(),
+            &'b'
+        );
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'b');
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+        assert_eq!(character_stream.read_char().unwrap(), 'c');
+        assert!(character_stream.peek_nth(0).is_none());
+    }
+
+    #[test]
+    fn peek_many_test() {
+        let mut character_stream = b"ab\xFFc".to_character_stream().peeky_multi();
+        let mut window = [Some('x'); 4];
+
+        assert_eq!(character_stream.peek_many(&mut window[..2]), 2);
+        assert_eq!(window, [Some('a'), Some('b'), Some('x'), Some('x')]);
+        assert_eq!(character_stream.peek_many(&mut window), 2);
+        assert_eq!(window, [Some('a'), Some('b'), None, None]);
+
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+        assert!(character_stream.read_char().is_err());
+        assert_eq!(character_stream.peek_many(&mut window), 1);
+        assert_eq!(window, [Some('c'), None, None, None]);
+    }
+
+    #[test]
+    fn unread_test() {
+        let mut character_stream = "ab".to_character_stream().peeky_multi();
+
+        let first = character_stream.read_char().unwrap();
+        character_stream.peek();
+        character_stream.unread(first);
+        assert_eq!(character_stream.chars_read(), 0);
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'a');
+
+        character_stream.unread_result(Err(CharacterError::NoBytesRead));
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::NoBytesRead)
+        ));
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+    }
+
+    #[test]
+    fn unread_str_test() {
+        let mut character_stream = ">>=;".to_character_stream().peeky();
+
+        let speculated: String = (0..3)
+            .map(|_| character_stream.read_char().unwrap())
+            .collect();
+        assert_eq!(speculated, ">>=");
+        character_stream.unread_str(&speculated[1..]);
+        character_stream.unread_chars(['>']);
+
+        let read: String = std::iter::from_fn(|| character_stream.read_char().ok()).collect();
+        assert_eq!(read, ">>=;");
+    }
+
+    #[test]
+    fn next_if_stream_test() {
+        let mut character_stream = b"ab\xFF".to_character_stream().peeky_multi();
+
+        character_stream.peek();
+        assert!(character_stream
+            .next_if(|character| *character == 'b')
+            .is_none());
+        assert_eq!(character_stream.next_if_eq('a').unwrap().unwrap(), 'a');
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'b');
+        assert_eq!(
+            character_stream
+                .next_if(char::is_ascii_alphabetic)
+                .unwrap()
+                .unwrap(),
+            'b'
+        );
+        assert!(character_stream.next_if(|_| false).unwrap().is_err());
+        assert!(character_stream.next_if(|_| true).is_none());
+    }
+
+    #[test]
+    fn peek_while_test() {
+        let mut character_stream = "12ab".to_character_stream().peeky_multi();
+
+        character_stream.peek();
+        assert_eq!(character_stream.peek_while(|c| c.is_ascii_digit()), "12");
+        assert_eq!(character_stream.peek_while(|_| true), "12ab");
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'2');
+        assert_eq!(character_stream.read_char().unwrap(), '1');
+        assert_eq!(character_stream.peek_while(char::is_alphabetic), "");
+    }
+
+    #[test]
+    fn peek_str_test() {
+        let mut character_stream = b"fn\xFFx".to_character_stream().peeky();
+
+        assert_eq!(character_stream.peek_str(0), "");
+        assert_eq!(character_stream.peek_str(1), "f");
+        assert_eq!(character_stream.peek_str(5), "fn");
+        assert_eq!(character_stream.read_char().unwrap(), 'f');
+        assert_eq!(character_stream.peek_str(2), "n");
+    }
+
+    #[test]
+    fn peek_char_stream_test() {
+        fn skip_to_digit(stream: &mut dyn PeekCharStream) -> Option<char> {
+            while let Some(Ok(character)) = stream.peek_nth(0) {
+                if character.is_ascii_digit() {
+                    return stream.read_char().ok();
+                }
+                stream.read_char().ok()?;
+            }
+            None
+        }
+
+        let mut peek = "ab1".to_character_stream().peeky();
+        let mut multi_peek = "x2".to_character_stream().peeky_multi();
+        assert_eq!(skip_to_digit(&mut peek), Some('1'));
+        assert_eq!(skip_to_digit(&mut multi_peek), Some('2'));
+        assert_eq!(skip_to_digit(&mut peek), None);
+    }
+
+    #[test]
+    fn peekable_into_inner_test() {
+        let mut character_stream = "abc".to_character_stream().peeky_multi();
+        character_stream.peek();
+        character_stream.peek();
+
+        let (mut stream, peeked) = character_stream.into_inner();
+        assert_eq!(peeked.len(), 2);
+        assert_eq!(peeked[0].as_ref().unwrap(), &'a');
+        assert_eq!(peeked[1].as_ref().unwrap(), &'b');
+        assert_eq!(stream.read_char().unwrap(), 'c');
+    }
+
+    #[test]
+    fn peek_position_test() {
+        let mut character_stream = "abc".to_character_stream().peeky_multi();
+
+        character_stream.peek();
+        let position = character_stream.peek_position();
+        assert_eq!(position, 1);
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'b');
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'c');
+
+        character_stream.set_peek_position(position);
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'b');
+
+        character_stream.set_peek_position(10);
+        assert_eq!(character_stream.peek_position(), 3);
+        assert!(character_stream.peek().is_none());
+    }
+
+    #[test]
+    fn peek_copied_test() {
+        let mut character_stream = b"a\xFF".to_character_stream().peeky();
+
+        if let Some(Ok(character)) = character_stream.peek_copied() {
+            assert_eq!(character, 'a');
+            assert_eq!(character_stream.read_char().unwrap(), character);
+        }
+        assert!(matches!(character_stream.peek_copied(), Some(Err(_))));
+        assert!(character_stream.read_char().is_err());
+        assert!(character_stream.peek_copied().is_none());
+    }
+
+    #[test]
+    fn take_while_test() {
+        let mut character_stream = b"123abc\xFFd".to_character_stream().peeky_multi();
+
+        assert_eq!(
+            character_stream.take_while(|c| c.is_ascii_digit()).unwrap(),
+            "123"
+        );
+        assert_eq!(
+            character_stream.take_while(|c| c.is_ascii_digit()).unwrap(),
+            ""
+        );
+        assert!(character_stream.take_while(char::is_alphabetic).is_err());
+        assert_eq!(character_stream.take_while(|_| true).unwrap(), "d");
+        assert_eq!(character_stream.take_while(|_| true).unwrap(), "");
+    }
+
+    #[test]
+    fn skip_while_test() {
+        let mut character_stream = " \t\r\n\u{3000}x  y".to_character_stream().peeky();
+
+        assert_eq!(character_stream.skip_whitespace(), 5);
+        assert_eq!(character_stream.skip_whitespace(), 0);
+        assert_eq!(character_stream.skip_while(|c| c != 'y'), 3);
+        assert_eq!(character_stream.read_char().unwrap(), 'y');
+        assert_eq!(character_stream.skip_while(|_| true), 0);
+    }
+
+    #[test]
+    fn read_until_test() {
+        let mut character_stream = "key=value<!-- x -->rest".to_character_stream();
+
+        assert_eq!(
+            character_stream.read_until('=', false).unwrap(),
+            Delimited::Found("key".to_string())
+        );
+        assert_eq!(
+            character_stream.read_until_str("-->", true).unwrap(),
+            Delimited::Found("value<!-- x -->".to_string())
+        );
+        let rest = character_stream.read_until(';', true).unwrap();
+        assert!(!rest.is_found());
+        assert_eq!(rest.as_str(), "rest");
+        assert_eq!(
+            character_stream.read_until_str("--", false).unwrap(),
+            Delimited::Eof(String::new())
+        );
+    }
+
+    #[test]
+    fn consume_str_test() {
+        let mut character_stream = "<<=<".to_character_stream().peeky_multi();
+
+        assert!(!character_stream.consume_str("<<<"));
+        assert!(!character_stream.consume_str("<<=<<"));
+        assert!(character_stream.consume_str("<<="));
+        assert!(character_stream.consume_str(""));
+        assert_eq!(character_stream.read_char().unwrap(), '<');
+    }
+
+    #[test]
+    fn find_test() {
+        let text = "junk\u{FFFD}-----BEGIN KEY-----\ndata\n-----END KEY-----\nrest";
+        let mut character_stream = text.to_character_stream().with_buffer_capacity(3);
+
+        assert_eq!(character_stream.find("-----BEGIN").unwrap(), Some(7));
+        assert_eq!(character_stream.byte_offset(), 7);
+        assert_eq!(character_stream.read_chars(5).unwrap(), "-----");
+
+        assert_eq!(character_stream.skip_until_str("\n").unwrap(), Some(26));
+        assert_eq!(character_stream.read_chars(4).unwrap(), "data");
+        assert_eq!(
+            character_stream.skip_until_str("KEY-----\n").unwrap(),
+            Some(41)
+        );
+        assert_eq!(character_stream.read_chars(4).unwrap(), "rest");
+        assert_eq!(character_stream.find("BEGIN").unwrap(), None);
+
+        let mut character_stream = b"ab\xFFcab".to_character_stream();
+        assert_eq!(character_stream.skip_until_str("ca").unwrap(), Some(3));
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+    }
+
+    #[test]
+    fn skip_chars_test() {
+        let mut character_stream = "abc\u{1F4BB}de"
+            .to_character_stream()
+            .with_buffer_capacity(2);
+
+        assert_eq!(character_stream.skip_chars(0).unwrap(), 0);
+        assert_eq!(character_stream.skip_chars(4).unwrap(), 4);
+        assert_eq!(character_stream.chars_read(), 4);
+        assert_eq!(character_stream.read_char().unwrap(), 'd');
+        assert_eq!(character_stream.skip_chars(5).unwrap(), 1);
+
+        let mut character_stream = b"ab\xFFc".to_character_stream();
+        assert!(matches!(character_stream.skip_chars(4), Err((2, _))));
+    }
+
+    #[test]
+    fn invalid_start_byte_test() {
+        let mut character_stream = b"\x80\xFFa".to_character_stream();
+
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::UnexpectedContinuation(0x80))
+        ));
+        let error = character_stream.read_char().unwrap_err();
+        assert!(matches!(error, CharacterError::InvalidStartByte(0xFF)));
+        assert_eq!(error.bytes(), Some(&[0xFF][..]));
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+    }
 }
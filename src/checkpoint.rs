@@ -0,0 +1,220 @@
+use std::io::Read;
+
+use crate::{CharStream, PeekableCharacterStream};
+
+/// A point in a [PeekableCharacterStream] that can be returned to.
+///
+/// Created by [PeekableCharacterStream::checkpoint].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checkpoint {
+    /// The amount of results that had been read when the checkpoint was made.
+    offset: usize,
+}
+
+impl<Reader: Read, PI, Stream: CharStream> PeekableCharacterStream<Reader, PI, Stream> {
+    /// Remember the current point in the stream, so it can be returned to with
+    /// [rollback](PeekableCharacterStream::rollback).
+    ///
+    /// Everything read from now on is kept until [release_checkpoints](PeekableCharacterStream::release_checkpoints)
+    /// is called, which is what allows backtracking without seeking the reader.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        if self.recorded_from.is_none() {
+            self.recorded_from = Some(self.consumed);
+        }
+
+        Checkpoint {
+            offset: self.consumed,
+        }
+    }
+
+    /// Return to `checkpoint`, so everything read since is read again.
+    ///
+    /// The checkpoint stays valid, so it can be rolled back to again later.
+    ///
+    /// Returns `false` if the checkpoint was released.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) -> bool {
+        let Some(from) = self.recorded_from else {
+            return false;
+        };
+        if checkpoint.offset < from || checkpoint.offset > self.consumed {
+            return false;
+        }
+
+        let replayed: Vec<_> = self.recorded.drain(checkpoint.offset - from..).collect();
+        for result in replayed.into_iter().rev() {
+            if result.is_ok() {
+                self.chars_read = self.chars_read.saturating_sub(1);
+            }
+            self.buffer.push_front(result);
+        }
+
+        self.consumed = checkpoint.offset;
+        self.position = 0;
+
+        true
+    }
+
+    /// Stop keeping read characters around, invalidating every [Checkpoint] and savepoint.
+    pub fn release_checkpoints(&mut self) {
+        self.recorded_from = None;
+        self.recorded.clear();
+        self.savepoints.clear();
+    }
+
+    /// Open a nested savepoint at the current point in the stream.
+    ///
+    /// Every savepoint must be closed with either [commit_savepoint](PeekableCharacterStream::commit_savepoint)
+    /// or [rollback_savepoint](PeekableCharacterStream::rollback_savepoint).
+    /// Once the outermost savepoint is closed, the read characters are no longer kept around,
+    /// which also releases any [Checkpoint]s.
+    pub fn push_savepoint(&mut self) {
+        let checkpoint = self.checkpoint();
+        self.savepoints.push(checkpoint);
+    }
+
+    /// Close the innermost savepoint, keeping everything read since it was opened.
+    ///
+    /// Returns `false` if there was no open savepoint.
+    pub fn commit_savepoint(&mut self) -> bool {
+        if self.savepoints.pop().is_none() {
+            return false;
+        }
+
+        if self.savepoints.is_empty() {
+            self.release_checkpoints();
+        }
+
+        true
+    }
+
+    /// Close the innermost savepoint, returning to where it was opened.
+    ///
+    /// Returns `false` if there was no open savepoint.
+    pub fn rollback_savepoint(&mut self) -> bool {
+        let Some(savepoint) = self.savepoints.pop() else {
+            return false;
+        };
+        let rolled_back = self.rollback(savepoint);
+
+        if self.savepoints.is_empty() {
+            self.release_checkpoints();
+        }
+
+        rolled_back
+    }
+
+    /// Run `parse` against the stream inside a savepoint.
+    ///
+    /// If `parse` returns `Err`, everything it read is read again afterwards.
+    pub fn try_parse<T, E>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let depth = self.savepoints.len();
+        self.push_savepoint();
+
+        let result = parse(self);
+
+        // Close anything `parse` left open, so the stack is balanced again.
+        self.savepoints.truncate(depth + 1);
+        if result.is_ok() {
+            self.commit_savepoint();
+        } else {
+            self.rollback_savepoint();
+        }
+
+        result
+    }
+
+    /// The amount of open savepoints.
+    pub fn savepoint_depth(&self) -> usize {
+        self.savepoints.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToCharacterStream;
+
+    #[test]
+    fn checkpoint_test() {
+        let mut character_stream = b"ab\xFFcd".to_character_stream().peeky();
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+
+        let checkpoint = character_stream.checkpoint();
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+        assert!(character_stream.read_char().is_err());
+        assert_eq!(character_stream.read_char().unwrap(), 'c');
+
+        assert!(character_stream.rollback(checkpoint));
+        assert_eq!(character_stream.chars_read(), 1);
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+        assert!(character_stream.read_char().is_err());
+
+        assert!(character_stream.rollback(checkpoint));
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+
+        character_stream.release_checkpoints();
+        assert!(!character_stream.rollback(checkpoint));
+        assert!(character_stream.read_char().is_err());
+        assert_eq!(character_stream.read_char().unwrap(), 'c');
+        assert_eq!(character_stream.read_char().unwrap(), 'd');
+    }
+
+    #[test]
+    fn savepoint_test() {
+        let mut character_stream = "abcdef".to_character_stream().peeky();
+        assert!(!character_stream.commit_savepoint());
+
+        character_stream.push_savepoint();
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+
+        character_stream.push_savepoint();
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+        assert_eq!(character_stream.savepoint_depth(), 2);
+        assert!(character_stream.rollback_savepoint());
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+
+        character_stream.push_savepoint();
+        assert_eq!(character_stream.read_char().unwrap(), 'c');
+        assert!(character_stream.commit_savepoint());
+
+        assert!(character_stream.rollback_savepoint());
+        assert_eq!(character_stream.savepoint_depth(), 0);
+        assert!(character_stream.recorded.is_empty());
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+
+        character_stream.push_savepoint();
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+        assert!(character_stream.commit_savepoint());
+        assert!(character_stream.recorded.is_empty());
+        assert_eq!(character_stream.read_char().unwrap(), 'c');
+    }
+
+    #[test]
+    fn try_parse_test() {
+        let mut character_stream = "1+x".to_character_stream().peeky();
+
+        let digit = |stream: &mut PeekableCharacterStream<_, _>| match stream.read_char() {
+            Ok(character) => character.to_digit(10).ok_or(character),
+            Err(_) => Err('\0'),
+        };
+
+        let sum = character_stream.try_parse(|stream| {
+            let left = stream.try_parse(digit)?;
+            stream.try_parse(|stream| match stream.read_char() {
+                Ok('+') => Ok(()),
+                _ => Err('+'),
+            })?;
+            let right = stream.try_parse(digit)?;
+            Ok(left + right)
+        });
+        assert_eq!(sum, Err('x'));
+        assert_eq!(character_stream.savepoint_depth(), 0);
+        assert_eq!(character_stream.read_char().unwrap(), '1');
+
+        assert_eq!(character_stream.try_parse(digit), Err('+'));
+        assert_eq!(character_stream.read_char().unwrap(), '+');
+    }
+}
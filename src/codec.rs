@@ -0,0 +1,83 @@
+use tokio_util::{
+    bytes::{Buf, BytesMut},
+    codec::Decoder,
+};
+
+use crate::{CharacterError, Decoded, Utf8Decoder};
+
+/// [Decoder] that turns the frames of a transport into UTF-8 characters.
+///
+/// Multi-byte characters split across frames are held back until their remaining bytes arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CharDecoder {
+    /// Whether or not we should care whether invalid bytes are detected.
+    ///
+    /// If `true`, then invalid byte sequences will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+}
+
+impl CharDecoder {
+    /// Create a [CharDecoder].
+    pub fn new(is_lossy: bool) -> Self {
+        Self { is_lossy }
+    }
+}
+
+impl Decoder for CharDecoder {
+    type Item = char;
+    type Error = CharacterError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<char>, CharacterError> {
+        let (result, used) = match Utf8Decoder::new(self.is_lossy).decode(src) {
+            Decoded::Char(character, used) => (Ok(Some(character)), used),
+            Decoded::Invalid(error, used) => (Err(error), used),
+            Decoded::Incomplete => return Ok(None),
+        };
+        src.advance(used);
+
+        result
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<char>, CharacterError> {
+        match self.decode(src)? {
+            Some(character) => Ok(Some(character)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                let bytes = src.split();
+                Utf8Decoder::new(self.is_lossy)
+                    .decode_truncated(&bytes)
+                    .map(Some)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_decoder_test() {
+        let mut decoder = CharDecoder::new(false);
+        let mut src = BytesMut::from(&b"a\xF0\x9F"[..]);
+
+        assert_eq!(decoder.decode(&mut src).unwrap(), Some('a'));
+        assert_eq!(decoder.decode(&mut src).unwrap(), None);
+
+        src.extend_from_slice(b"\x92\xBB\xE2");
+        assert_eq!(decoder.decode(&mut src).unwrap(), Some('\u{1F4BB}'));
+        assert_eq!(decoder.decode(&mut src).unwrap(), None);
+        assert!(matches!(
+            decoder.decode_eof(&mut src),
+            Err(CharacterError::TruncatedSequence { bytes, expected: 3, got: 1 }) if bytes == [0xE2]
+        ));
+        assert_eq!(decoder.decode_eof(&mut src).unwrap(), None);
+
+        src.extend_from_slice(b"\xE2AB");
+        assert!(decoder.decode(&mut src).is_err());
+        assert_eq!(decoder.decode(&mut src).unwrap(), Some('A'));
+        assert_eq!(src.len(), 1);
+    }
+}
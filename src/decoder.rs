@@ -0,0 +1,334 @@
+use std::io::{self, Read};
+
+use crate::{
+    is_encoded_surrogate, read_bytes_into, remaining_byte_count, surrogate_code_point, CharStream,
+    CharacterError, CharacterIterator, CharacterStreamResult, MultiPeek, Peek,
+    PeekableCharacterStream, SurrogatePolicy, INTERRUPTED_MAX,
+};
+
+/// Decodes characters from a stream of bytes.
+///
+/// Implement this to read custom encodings through [DecoderCharacterStream], which can then be
+/// peeked with [PeekableCharacterStream] and iterated with [CharacterIterator].
+pub trait Decoder {
+    /// Attempts to decode the next character from `reader`.
+    ///
+    /// Should return [NoBytesRead](crate::CharacterError::NoBytesRead) once `reader` has ended.
+    fn decode_next(&mut self, reader: &mut impl Read) -> CharacterStreamResult;
+
+    /// Does the decoder replace invalid input with a U+FFFD?
+    fn is_lossy(&self) -> bool {
+        false
+    }
+}
+
+/// The outcome of decoding the character at the start of some bytes.
+#[derive(Debug)]
+pub(crate) enum Decoded {
+    /// The character decoded from the first `len` bytes, or a U+FFFD replacing them in lossy mode.
+    Char(char, usize),
+    /// The first `len` bytes are not a valid character.
+    Invalid(CharacterError, usize),
+    /// The bytes are the start of a character, but more are needed.
+    Incomplete,
+}
+
+/// The UTF-8 [Decoder], which [CharacterStream](crate::CharacterStream) decodes with as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Utf8Decoder {
+    /// Whether or not we should care whether invalid bytes are detected.
+    ///
+    /// If `true`, then invalid byte sequences will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+    /// See [CharacterStream::strict](crate::CharacterStream::strict).
+    pub strict: bool,
+    /// See [CharacterStream::surrogate_policy](crate::CharacterStream::surrogate_policy).
+    pub surrogate_policy: SurrogatePolicy,
+    /// A byte read from the reader that ended an invalid sequence, it starts the next character.
+    lookahead: Option<u8>,
+}
+
+impl Utf8Decoder {
+    /// Create a [Utf8Decoder].
+    pub fn new(is_lossy: bool) -> Self {
+        Self {
+            is_lossy,
+            ..Default::default()
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// See [CharacterStream::strict](crate::CharacterStream::strict).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// See [CharacterStream::surrogate_policy](crate::CharacterStream::surrogate_policy).
+    pub fn surrogate_policy(mut self, surrogate_policy: SurrogatePolicy) -> Self {
+        self.surrogate_policy = surrogate_policy;
+        self
+    }
+
+    /// Decode the character at the start of `bytes`.
+    ///
+    /// A starting byte that isn't followed by enough continuation bytes is invalid on its own,
+    /// the byte that ended the sequence early is left for the next character.
+    pub(crate) fn decode(&self, bytes: &[u8]) -> Decoded {
+        let Some(&first_byte) = bytes.first() else {
+            return Decoded::Incomplete;
+        };
+        let expected = match remaining_byte_count(first_byte) {
+            Some(remaining_count) => remaining_count + 1,
+            None => return self.invalid(1, || CharacterError::invalid_start(first_byte)),
+        };
+
+        let available = &bytes[..bytes.len().min(expected)];
+        if let Some(index) = available[1..].iter().position(|byte| byte & 0xC0 != 0x80) {
+            let len = index + 1;
+            return self.invalid(len, || {
+                CharacterError::InvalidSequence(bytes[..len].to_vec())
+            });
+        }
+
+        if bytes.len() < expected {
+            return Decoded::Incomplete;
+        }
+
+        let read = &bytes[..expected];
+
+        if is_encoded_surrogate(read) {
+            match self.surrogate_policy {
+                SurrogatePolicy::Reject => {}
+                SurrogatePolicy::Replace | SurrogatePolicy::PreserveAsReplacement => {
+                    return Decoded::Char('\u{FFFD}', expected)
+                }
+                SurrogatePolicy::Wtf8 => {
+                    let error = CharacterError::Surrogate {
+                        bytes: read.to_vec(),
+                        code_point: surrogate_code_point(read),
+                    };
+                    return Decoded::Invalid(error, expected);
+                }
+            }
+        }
+
+        // A complete sequence that is valid UTF-8 always holds exactly one character.
+        match simdutf8::basic::from_utf8(read) {
+            Ok(string) => Decoded::Char(string.chars().next().unwrap_or_default(), expected),
+            Err(_) if self.strict => self.invalid(expected, || strict_error(read.to_vec())),
+            Err(_) => self.invalid(expected, || CharacterError::utf8(read.to_vec())),
+        }
+    }
+
+    /// Handle `bytes`, an incomplete character at the end of the input.
+    pub(crate) fn decode_truncated(&self, bytes: &[u8]) -> CharacterStreamResult {
+        if self.is_lossy {
+            Ok('\u{FFFD}')
+        } else {
+            Err(CharacterError::truncated(bytes.to_vec()))
+        }
+    }
+
+    /// The first `len` bytes are invalid, replace them in lossy mode.
+    fn invalid(&self, len: usize, error: impl FnOnce() -> CharacterError) -> Decoded {
+        if self.is_lossy {
+            Decoded::Char('\u{FFFD}', len)
+        } else {
+            Decoded::Invalid(error(), len)
+        }
+    }
+}
+
+/// Describe why `bytes`, a complete sequence of continuation bytes that is not valid UTF-8, was
+/// rejected.
+fn strict_error(bytes: Vec<u8>) -> CharacterError {
+    let code_point = bytes[1..].iter().fold(
+        (bytes[0] & (0x7F >> bytes.len())) as u32,
+        |code_point, byte| (code_point << 6) | (byte & 0x3F) as u32,
+    );
+    let minimum = match bytes.len() {
+        2 => 0x80,
+        3 => 0x800,
+        _ => 0x10000,
+    };
+
+    if code_point < minimum {
+        CharacterError::Overlong { bytes }
+    } else if (0xD800..=0xDFFF).contains(&code_point) {
+        CharacterError::Surrogate {
+            bytes,
+            code_point: code_point as u16,
+        }
+    } else if code_point > 0x10FFFF {
+        CharacterError::OutOfRange { bytes, code_point }
+    } else {
+        CharacterError::utf8(bytes)
+    }
+}
+
+impl Decoder for Utf8Decoder {
+    fn decode_next(&mut self, reader: &mut impl Read) -> CharacterStreamResult {
+        let mut bytes = [0u8; 4];
+        let mut len = 0;
+
+        if let Some(byte) = self.lookahead.take() {
+            bytes[0] = byte;
+            len = 1;
+        }
+
+        let (result, used) = loop {
+            match self.decode(&bytes[..len]) {
+                Decoded::Char(character, used) => break (Ok(character), used),
+                Decoded::Invalid(error, used) => break (Err(error), used),
+                Decoded::Incomplete => {}
+            }
+
+            match read_bytes_into(reader, &mut bytes[len..len + 1]) {
+                Ok(0) if len == 0 => return Err(CharacterError::NoBytesRead),
+                Ok(0) => return self.decode_truncated(&bytes[..len]),
+                Ok(_) => len += 1,
+                Err((_, error)) if error.kind() == io::ErrorKind::Interrupted && len > 0 => {}
+                Err((_, error)) => {
+                    return Err(CharacterError::IoError {
+                        bytes: bytes[..len].to_vec(),
+                        error,
+                    })
+                }
+            }
+        };
+
+        // Only the byte that ended an invalid sequence early is left over.
+        if used < len {
+            self.lookahead = Some(bytes[used]);
+        }
+
+        result
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+}
+
+/// Wrapper struct for any stream, decoding its bytes with a [Decoder].
+pub struct DecoderCharacterStream<Reader: Read, D: Decoder> {
+    /// The stream from which the incoming bytes are from.
+    pub stream: Reader,
+    /// The decoder that turns the bytes into characters.
+    pub decoder: D,
+}
+
+impl<Reader: Read, D: Decoder> DecoderCharacterStream<Reader, D> {
+    /// Create a [DecoderCharacterStream] from a stream and a decoder.
+    pub fn new(stream: Reader, decoder: D) -> Self {
+        Self { stream, decoder }
+    }
+
+    /// Wrap `self` into a single-peek [PeekableCharacterStream].
+    pub fn peeky(self) -> PeekableCharacterStream<Reader, Peek, Self> {
+        PeekableCharacterStream::from_stream(self)
+    }
+
+    /// Wrap `self` into a multi-peek [PeekableCharacterStream].
+    pub fn peeky_multi(self) -> PeekableCharacterStream<Reader, MultiPeek, Self> {
+        PeekableCharacterStream::from_stream(self)
+    }
+}
+
+impl<Reader: Read, D: Decoder> CharStream for DecoderCharacterStream<Reader, D> {
+    fn read_char(&mut self) -> CharacterStreamResult {
+        self.decoder.decode_next(&mut self.stream)
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.decoder.is_lossy()
+    }
+}
+
+impl<Reader: Read + std::fmt::Debug, D: Decoder + std::fmt::Debug> std::fmt::Debug
+    for DecoderCharacterStream<Reader, D>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DecoderCharacterStream")
+            .field("stream", &self.stream)
+            .field("decoder", &self.decoder)
+            .finish()
+    }
+}
+
+impl<Reader: Read, D: Decoder> IntoIterator for DecoderCharacterStream<Reader, D> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{read_bytes, Peekable};
+
+    /// Decoder for text where every byte is shifted up by one.
+    struct ShiftDecoder;
+
+    impl Decoder for ShiftDecoder {
+        fn decode_next(&mut self, reader: &mut impl Read) -> CharacterStreamResult {
+            Ok((read_bytes(reader, 1)?[0] - 1) as char)
+        }
+    }
+
+    #[test]
+    fn custom_decoder_test() {
+        let mut character_stream =
+            DecoderCharacterStream::new(Cursor::new(b"bcd"), ShiftDecoder).peeky();
+
+        assert_eq!(character_stream.peek().unwrap().as_ref().unwrap(), &'a');
+        let read: String = character_stream
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, "abc");
+
+        let read: String =
+            DecoderCharacterStream::new(Cursor::new("\u{1F4BB}"), Utf8Decoder::new(false))
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(read, "\u{1F4BB}");
+    }
+
+    #[test]
+    fn utf8_decoder_settings_test() {
+        let decode = |decoder: Utf8Decoder, bytes: &'static [u8]| {
+            DecoderCharacterStream::new(Cursor::new(bytes), decoder).read_char()
+        };
+
+        assert!(matches!(
+            decode(Utf8Decoder::new(false).strict(true), b"\xC0\xAF"),
+            Err(CharacterError::Overlong { .. })
+        ));
+        assert_eq!(
+            decode(
+                Utf8Decoder::new(false).surrogate_policy(SurrogatePolicy::Replace),
+                b"\xED\xA0\xBD"
+            )
+            .unwrap(),
+            '\u{FFFD}'
+        );
+        assert!(matches!(
+            decode(Utf8Decoder::new(false), b"\xE2\x82"),
+            Err(CharacterError::TruncatedSequence { .. })
+        ));
+    }
+}
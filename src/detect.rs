@@ -0,0 +1,220 @@
+use std::io::{Chain, Cursor, Read};
+
+use crate::{
+    CharStream, CharacterError, CharacterIterator, CharacterStream, CharacterStreamResult,
+    Endianness, Utf16CharacterStream, Utf32CharacterStream, INTERRUPTED_MAX,
+};
+
+/// The reader of a [DetectedCharacterStream], which replays the sniffed bytes that were not a BOM.
+pub type SniffedReader<Reader> = Chain<Cursor<Vec<u8>>, Reader>;
+
+/// Unicode encodings that can be told apart by their byte order mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeEncoding {
+    Utf8,
+    Utf16(Endianness),
+    Utf32(Endianness),
+}
+
+impl UnicodeEncoding {
+    /// Detect the encoding from the byte order mark at the start of `bytes`.
+    ///
+    /// Returns the encoding along with the length of the byte order mark.
+    pub fn from_bom(bytes: &[u8]) -> Option<(Self, usize)> {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => Some((UnicodeEncoding::Utf8, 3)),
+            [0xFF, 0xFE, 0x00, 0x00, ..] => Some((UnicodeEncoding::Utf32(Endianness::Little), 4)),
+            [0x00, 0x00, 0xFE, 0xFF, ..] => Some((UnicodeEncoding::Utf32(Endianness::Big), 4)),
+            [0xFF, 0xFE, ..] => Some((UnicodeEncoding::Utf16(Endianness::Little), 2)),
+            [0xFE, 0xFF, ..] => Some((UnicodeEncoding::Utf16(Endianness::Big), 2)),
+            _ => None,
+        }
+    }
+}
+
+/// A stream whose encoding was picked by [CharacterStream::detect].
+pub enum DetectedCharacterStream<Reader: Read> {
+    Utf8(CharacterStream<Reader>),
+    Utf16(Utf16CharacterStream<Reader>),
+    Utf32(Utf32CharacterStream<Reader>),
+}
+
+impl<Reader: Read> DetectedCharacterStream<Reader> {
+    /// The encoding that was detected.
+    pub fn encoding(&self) -> UnicodeEncoding {
+        match self {
+            DetectedCharacterStream::Utf8(_) => UnicodeEncoding::Utf8,
+            DetectedCharacterStream::Utf16(stream) => UnicodeEncoding::Utf16(stream.endianness),
+            DetectedCharacterStream::Utf32(stream) => UnicodeEncoding::Utf32(stream.endianness),
+        }
+    }
+
+    /// Kinda builder pattern.
+    pub fn lossy(self, is_lossy: bool) -> Self {
+        match self {
+            DetectedCharacterStream::Utf8(stream) => {
+                DetectedCharacterStream::Utf8(stream.lossy(is_lossy))
+            }
+            DetectedCharacterStream::Utf16(stream) => {
+                DetectedCharacterStream::Utf16(stream.lossy(is_lossy))
+            }
+            DetectedCharacterStream::Utf32(stream) => {
+                DetectedCharacterStream::Utf32(stream.lossy(is_lossy))
+            }
+        }
+    }
+}
+
+impl<Reader: Read> CharacterStream<Reader> {
+    /// Create a stream whose encoding is picked from the byte order mark at the start of `reader`.
+    ///
+    /// The byte order mark is consumed. If there is none, then UTF-8 is assumed.
+    pub fn detect(
+        reader: Reader,
+    ) -> Result<DetectedCharacterStream<SniffedReader<Reader>>, CharacterError> {
+        let mut reader = reader;
+        let mut prefix = vec![];
+
+        if let Err(error) = (&mut reader).take(4).read_to_end(&mut prefix) {
+            return Err(CharacterError::IoError {
+                bytes: prefix,
+                error,
+            });
+        }
+
+        let (encoding, bom_length) =
+            UnicodeEncoding::from_bom(&prefix).unwrap_or((UnicodeEncoding::Utf8, 0));
+        let reader = Cursor::new(prefix.split_off(bom_length)).chain(reader);
+
+        Ok(match encoding {
+            UnicodeEncoding::Utf8 => DetectedCharacterStream::Utf8(CharacterStream::from(reader)),
+            UnicodeEncoding::Utf16(endianness) => {
+                DetectedCharacterStream::Utf16(Utf16CharacterStream::new(reader, endianness, false))
+            }
+            UnicodeEncoding::Utf32(endianness) => {
+                DetectedCharacterStream::Utf32(Utf32CharacterStream::new(reader, endianness, false))
+            }
+        })
+    }
+}
+
+/// The amount of bytes sampled when guessing the encoding of BOM-less input.
+#[cfg(feature = "charset-detection")]
+const DETECTION_SAMPLE_SIZE: usize = 4096;
+
+#[cfg(feature = "charset-detection")]
+impl<Reader: Read> CharacterStream<Reader> {
+    /// Create a lossy stream whose encoding is picked from the start of `reader`.
+    ///
+    /// UTF-8 and UTF-16 byte order marks are honored and consumed. Otherwise the encoding is
+    /// guessed from a sample of the input, choosing between UTF-8 and common legacy encodings.
+    pub fn detect_lossy(
+        reader: Reader,
+    ) -> Result<crate::EncodingCharacterStream<SniffedReader<Reader>>, CharacterError> {
+        let mut reader = reader;
+        let mut sample = vec![];
+
+        if let Err(error) = (&mut reader)
+            .take(DETECTION_SAMPLE_SIZE as u64)
+            .read_to_end(&mut sample)
+        {
+            return Err(CharacterError::IoError {
+                bytes: sample,
+                error,
+            });
+        }
+
+        let (encoding, bom_length) = encoding_rs::Encoding::for_bom(&sample).unwrap_or_else(|| {
+            let mut detector = chardetng::EncodingDetector::new();
+            detector.feed(&sample, sample.len() < DETECTION_SAMPLE_SIZE);
+            (detector.guess(None, true), 0)
+        });
+        let reader = Cursor::new(sample.split_off(bom_length)).chain(reader);
+
+        Ok(crate::EncodingCharacterStream::new(reader, encoding, true))
+    }
+}
+
+impl<Reader: Read> CharStream for DetectedCharacterStream<Reader> {
+    fn read_char(&mut self) -> CharacterStreamResult {
+        match self {
+            DetectedCharacterStream::Utf8(stream) => stream.read_char(),
+            DetectedCharacterStream::Utf16(stream) => stream.read_char(),
+            DetectedCharacterStream::Utf32(stream) => stream.read_char(),
+        }
+    }
+
+    fn is_lossy(&self) -> bool {
+        match self {
+            DetectedCharacterStream::Utf8(stream) => stream.is_lossy(),
+            DetectedCharacterStream::Utf16(stream) => stream.is_lossy(),
+            DetectedCharacterStream::Utf32(stream) => stream.is_lossy(),
+        }
+    }
+}
+
+impl<Reader: Read> IntoIterator for DetectedCharacterStream<Reader> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_test() {
+        let utf16: Vec<u8> = "\u{FEFF}hi"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        let utf32: Vec<u8> = "\u{FEFF}hi"
+            .chars()
+            .flat_map(|c| (c as u32).to_le_bytes())
+            .collect();
+        let cases: [(Vec<u8>, UnicodeEncoding); 4] = [
+            ("\u{FEFF}hi".into(), UnicodeEncoding::Utf8),
+            ("hi".into(), UnicodeEncoding::Utf8),
+            (utf16, UnicodeEncoding::Utf16(Endianness::Big)),
+            (utf32, UnicodeEncoding::Utf32(Endianness::Little)),
+        ];
+
+        for (bytes, encoding) in cases {
+            let character_stream = CharacterStream::detect(Cursor::new(bytes)).unwrap();
+            assert_eq!(character_stream.encoding(), encoding);
+
+            let read: String = character_stream
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(read, "hi");
+        }
+    }
+
+    #[cfg(feature = "charset-detection")]
+    #[test]
+    fn detect_lossy_test() {
+        let text = "\u{41F}\u{440}\u{438}\u{432}\u{435}\u{442}, \u{43C}\u{438}\u{440}! ".repeat(20);
+        let (windows_1251, _, _) = encoding_rs::WINDOWS_1251.encode(&text);
+        let cases = [
+            (text.clone().into_bytes(), encoding_rs::UTF_8),
+            (windows_1251.into_owned(), encoding_rs::WINDOWS_1251),
+        ];
+
+        for (bytes, encoding) in cases {
+            let character_stream = CharacterStream::detect_lossy(Cursor::new(bytes)).unwrap();
+            assert_eq!(character_stream.encoding(), encoding);
+
+            let read: String = character_stream
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(read, text);
+        }
+    }
+}
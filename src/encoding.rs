@@ -0,0 +1,219 @@
+use std::{collections::VecDeque, io::Read};
+
+use encoding_rs::{DecoderResult, Encoding};
+
+use crate::{
+    CharStream, CharacterError, CharacterIterator, CharacterStreamResult, INTERRUPTED_MAX,
+};
+
+/// The amount of bytes read from the stream at a time.
+const CHUNK_SIZE: usize = 1024;
+
+/// Wrapper struct for any stream that holds text in an [Encoding] supported by [encoding_rs].
+///
+/// Bytes are read in chunks, and the decoded characters are buffered until they are read.
+pub struct EncodingCharacterStream<Reader: Read> {
+    /// The stream from which the incoming bytes are from.
+    pub stream: Reader,
+    /// Whether or not we should care whether invalid bytes are detected.
+    ///
+    /// If `true`, then invalid byte sequences will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+    decoder: encoding_rs::Decoder,
+    decoded: VecDeque<CharacterStreamResult>,
+    finished: bool,
+}
+
+impl<Reader: Read> EncodingCharacterStream<Reader> {
+    /// Create an [EncodingCharacterStream] from a stream.
+    ///
+    /// Set `is_lossy` to `true` if you don't want to handle invalid byte sequences.
+    pub fn new(stream: Reader, encoding: &'static Encoding, is_lossy: bool) -> Self {
+        Self {
+            stream,
+            is_lossy,
+            decoder: encoding.new_decoder_without_bom_handling(),
+            decoded: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Create an [EncodingCharacterStream] from a stream, using the encoding with the WHATWG `label`.
+    ///
+    /// Returns `None` if the label is not known.
+    pub fn for_label(stream: Reader, label: &str, is_lossy: bool) -> Option<Self> {
+        Encoding::for_label(label.as_bytes()).map(|encoding| Self::new(stream, encoding, is_lossy))
+    }
+
+    /// Create an [EncodingCharacterStream] that decodes Shift-JIS.
+    #[cfg(feature = "cjk")]
+    pub fn shift_jis(stream: Reader, is_lossy: bool) -> Self {
+        Self::new(stream, encoding_rs::SHIFT_JIS, is_lossy)
+    }
+
+    /// Create an [EncodingCharacterStream] that decodes EUC-JP.
+    #[cfg(feature = "cjk")]
+    pub fn euc_jp(stream: Reader, is_lossy: bool) -> Self {
+        Self::new(stream, encoding_rs::EUC_JP, is_lossy)
+    }
+
+    /// Kinda builder pattern.
+    pub fn lossy(mut self, is_lossy: bool) -> Self {
+        self.is_lossy = is_lossy;
+        self
+    }
+
+    /// The encoding the stream is decoded with.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.decoder.encoding()
+    }
+
+    /// Read the next chunk of bytes from the stream and decode it.
+    fn decode_chunk(&mut self) -> Result<(), CharacterError> {
+        let mut chunk = [0u8; CHUNK_SIZE];
+        let read = self
+            .stream
+            .read(&mut chunk)
+            .map_err(|error| CharacterError::IoError {
+                bytes: vec![],
+                error,
+            })?;
+        let last = read == 0;
+        let mut input = &chunk[..read];
+        let mut output = String::new();
+
+        loop {
+            output.reserve(
+                self.decoder
+                    .max_utf8_buffer_length_without_replacement(input.len())
+                    .unwrap_or(input.len() * 3 + 16),
+            );
+
+            let (result, consumed) =
+                self.decoder
+                    .decode_to_string_without_replacement(input, &mut output, last);
+            self.decoded.extend(output.drain(..).map(Ok));
+
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => {}
+                DecoderResult::Malformed(length, extra) => {
+                    let end = consumed - extra as usize;
+                    let bytes = input[end.saturating_sub(length as usize)..end].to_vec();
+
+                    self.decoded.push_back(if self.is_lossy {
+                        Ok('\u{FFFD}')
+                    } else {
+                        Err(CharacterError::InvalidSequence(bytes))
+                    });
+                }
+            }
+
+            input = &input[consumed..];
+        }
+
+        self.finished = last;
+        Ok(())
+    }
+}
+
+impl<Reader: Read> CharStream for EncodingCharacterStream<Reader> {
+    /// Attempts to read a character from the stream.
+    ///
+    /// If `is_lossy` is set to `true`, then invalid byte sequences will be a U+FFFD.
+    ///
+    /// If `is_lossy` is set to `false`, then invalid byte sequences will be returned in addition to a parse error.
+    fn read_char(&mut self) -> CharacterStreamResult {
+        loop {
+            if let Some(result) = self.decoded.pop_front() {
+                return result;
+            }
+
+            if self.finished {
+                return Err(CharacterError::NoBytesRead);
+            }
+
+            self.decode_chunk()?;
+        }
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+}
+
+impl<Reader: Read + std::fmt::Debug> std::fmt::Debug for EncodingCharacterStream<Reader> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncodingCharacterStream")
+            .field("stream", &self.stream)
+            .field("encoding", &self.encoding())
+            .field("is_lossy", &self.is_lossy)
+            .finish()
+    }
+}
+
+impl<Reader: Read> IntoIterator for EncodingCharacterStream<Reader> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn encoding_label_test() {
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode("abc\u{65E5}\u{672C}");
+        let read: String =
+            EncodingCharacterStream::for_label(Cursor::new(bytes), "shift_jis", false)
+                .unwrap()
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(read, "abc\u{65E5}\u{672C}");
+
+        let read: Vec<CharacterStreamResult> =
+            EncodingCharacterStream::for_label(Cursor::new(b"a\xFFb"), "shift_jis", false)
+                .unwrap()
+                .into_iter()
+                .collect();
+        assert_eq!(read[0].as_ref().unwrap(), &'a');
+        assert_eq!(read[1].as_ref().unwrap_err().bytes(), Some(&[0xFF][..]));
+        assert_eq!(read[2].as_ref().unwrap(), &'b');
+
+        assert!(
+            EncodingCharacterStream::for_label(Cursor::new(b""), "not-an-encoding", false)
+                .is_none()
+        );
+    }
+
+    #[cfg(feature = "cjk")]
+    #[test]
+    fn cjk_test() {
+        let text = "\u{65E5}\u{672C}\u{8A9E}\u{FF71}abc";
+
+        for (encoding, new) in [
+            (
+                encoding_rs::SHIFT_JIS,
+                EncodingCharacterStream::shift_jis as fn(_, _) -> _,
+            ),
+            (encoding_rs::EUC_JP, EncodingCharacterStream::euc_jp),
+        ] {
+            let (bytes, _, _) = encoding.encode(text);
+            let read: String = new(Cursor::new(bytes.into_owned()), false)
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(read, text);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::io;
 use thiserror::Error;
 
+use crate::Position;
+
 #[derive(Error, Debug)]
 /// Type to represent stream errors.
 pub enum CharacterError {
@@ -9,19 +11,324 @@ pub enum CharacterError {
     #[error("An IO error occurred on bytes {:?}: {}", .bytes, .error)]
     IoError { bytes: Vec<u8>, error: io::Error },
 
-    #[error("An error occurred on bytes {:?}: {}", .bytes, .error)]
+    #[error("An error occurred on bytes {:?}: {:#}", .bytes, .error)]
     Other {
         bytes: Vec<u8>,
         error: anyhow::Error,
     },
+    /// A byte that can't start a UTF-8 sequence.
+    #[error("Invalid starting byte {:#04X}.", .0)]
+    InvalidStartByte(u8),
+    /// A continuation byte where a character should have started.
+    #[error("Unexpected continuation byte {:#04X}.", .0)]
+    UnexpectedContinuation(u8),
+    /// The stream ended before the sequence started by the first of `bytes` was complete.
+    #[error("The stream ended in the middle of a character, got {} of {} bytes {:?}.", .got, .expected, .bytes)]
+    TruncatedSequence {
+        bytes: Vec<u8>,
+        /// The length of the sequence announced by the starting byte.
+        expected: usize,
+        /// The amount of bytes that were read.
+        got: usize,
+    },
+    /// A byte sequence that isn't valid in the encoding, like a UTF-8 starting byte that isn't
+    /// followed by enough continuation bytes.
+    #[error("Invalid byte sequence {:?}.", .0)]
+    InvalidSequence(Vec<u8>),
+    #[error("Invalid UTF-8 in bytes {:?}, valid up to byte {}.", .bytes, .valid_up_to)]
+    Utf8 {
+        bytes: Vec<u8>,
+        /// The amount of bytes that were valid UTF-8.
+        valid_up_to: usize,
+        /// The length of the invalid byte sequence, or `None` if the bytes ended unexpectedly.
+        error_len: Option<usize>,
+    },
+    #[error("Encoded surrogate {:#X} in bytes {:?}.", .code_point, .bytes)]
+    Surrogate {
+        bytes: Vec<u8>,
+        /// The surrogate code point the bytes encode.
+        code_point: u16,
+    },
+    #[error("Overlong encoding in bytes {:?}.", .bytes)]
+    Overlong { bytes: Vec<u8> },
+    #[error("Code point {:#X} in bytes {:?} is above U+10FFFF.", .code_point, .bytes)]
+    OutOfRange {
+        bytes: Vec<u8>,
+        /// The code point the bytes encode.
+        code_point: u32,
+    },
+    /// A decoded character that [classify](crate::CharacterStream::classify) rejected.
+    #[error("Rejected {:?} in bytes {:?}: {}.", .character, .bytes, .reason)]
+    Rejected {
+        bytes: Vec<u8>,
+        character: char,
+        /// Why the character was rejected.
+        reason: &'static str,
+    },
+    #[error("The peek buffer exceeded its limit of {0} characters.")]
+    PeekLimitExceeded(usize),
+    /// The stream is not ready, retry once it is readable.
+    ///
+    /// Only returned in [non-blocking](crate::CharacterStream::nonblocking) mode.
+    #[error("The stream would block.")]
+    WouldBlock,
+    /// A read was [Interrupted](io::ErrorKind::Interrupted) more often in a row than allowed.
+    #[error("The stream was interrupted {0} times in a row.")]
+    TooManyInterrupts(usize),
+    /// The input didn't match what [expect](crate::PeekCharStream::expect) or
+    /// [expect_str](crate::PeekCharStream::expect_str) was looking for.
+    #[error("Expected {:?}, found {}{}.", .expected, describe_found(.found), describe_position(.position))]
+    Unexpected {
+        expected: String,
+        /// The character found instead, or `None` at the end of the stream.
+        found: Option<char>,
+        /// Where the expected input should have started, if the stream keeps track of it.
+        position: Option<Position>,
+    },
+    /// An error with a label attached by [context](crate::CharacterIterator::context).
+    #[error("{label}: {source}")]
+    Context {
+        label: &'static str,
+        #[source]
+        source: Box<CharacterError>,
+    },
+    /// An error read by [Lines](crate::Lines), with the number of the line it occurred on.
+    #[error("line {line}: {source}")]
+    Line {
+        /// The line number, starting at 1.
+        line: usize,
+        #[source]
+        source: Box<CharacterError>,
+    },
+    /// The DFA given to [read_match](crate::PeekableCharacterStream::read_match) gave up.
+    #[cfg(feature = "regex-automata")]
+    #[error("The DFA failed: {0}")]
+    Regex(regex_automata::MatchError),
+    /// A literal or delimited region wasn't closed before the end of the stream.
+    #[error("Unterminated {what}{}.", describe_position(.start))]
+    Unterminated {
+        /// What wasn't terminated, like "string".
+        what: &'static str,
+        /// Where it starts, if the stream keeps track of it.
+        start: Option<Position>,
+    },
+}
+
+fn describe_found(found: &Option<char>) -> String {
+    match found {
+        Some(character) => format!("{:?}", character),
+        None => "the end of the stream".to_string(),
+    }
+}
+
+fn describe_position(position: &Option<Position>) -> String {
+    match position {
+        Some(position) => format!(" at {}", position),
+        None => String::new(),
+    }
 }
 
 impl CharacterError {
+    /// Create a [Utf8](CharacterError::Utf8) error describing why `bytes` are not valid UTF-8.
+    pub(crate) fn utf8(bytes: Vec<u8>) -> Self {
+        let (valid_up_to, error_len) = match simdutf8::compat::from_utf8(&bytes) {
+            Ok(_) => (bytes.len(), None),
+            Err(error) => (error.valid_up_to(), error.error_len()),
+        };
+
+        CharacterError::Utf8 {
+            bytes,
+            valid_up_to,
+            error_len,
+        }
+    }
+
+    /// Create an error for `byte`, which [remaining_byte_count](crate::remaining_byte_count)
+    /// rejected as the start of a character.
+    pub(crate) fn invalid_start(byte: u8) -> Self {
+        if byte & 0xC0 == 0x80 {
+            CharacterError::UnexpectedContinuation(byte)
+        } else {
+            CharacterError::InvalidStartByte(byte)
+        }
+    }
+
+    /// Create a [TruncatedSequence](CharacterError::TruncatedSequence) error for the incomplete
+    /// sequence `bytes`.
+    pub(crate) fn truncated(bytes: Vec<u8>) -> Self {
+        let expected = bytes
+            .first()
+            .and_then(|&byte| crate::remaining_byte_count(byte))
+            .map_or(bytes.len(), |remaining_count| remaining_count + 1);
+
+        let got = bytes.len();
+
+        CharacterError::TruncatedSequence {
+            bytes,
+            expected,
+            got,
+        }
+    }
+
+    /// Create an [InvalidSequence](CharacterError::InvalidSequence) error if a continuation byte is
+    /// missing from `bytes`, a complete sequence that is not valid UTF-8, or a
+    /// [Utf8](CharacterError::Utf8) error otherwise.
+    pub(crate) fn invalid_utf8(bytes: Vec<u8>) -> Self {
+        if bytes[1..].iter().any(|byte| byte & 0xC0 != 0x80) {
+            CharacterError::InvalidSequence(bytes)
+        } else {
+            CharacterError::utf8(bytes)
+        }
+    }
+
+    /// Is this an error about the bytes of a character, rather than about reading them?
+    pub(crate) fn is_decode_error(&self) -> bool {
+        match self {
+            CharacterError::Other { .. }
+            | CharacterError::InvalidStartByte(_)
+            | CharacterError::UnexpectedContinuation(_)
+            | CharacterError::TruncatedSequence { .. }
+            | CharacterError::InvalidSequence(_)
+            | CharacterError::Utf8 { .. }
+            | CharacterError::Surrogate { .. }
+            | CharacterError::Overlong { .. }
+            | CharacterError::OutOfRange { .. }
+            | CharacterError::Rejected { .. } => true,
+            CharacterError::NoBytesRead
+            | CharacterError::IoError { .. }
+            | CharacterError::PeekLimitExceeded(_)
+            | CharacterError::WouldBlock
+            | CharacterError::TooManyInterrupts(_)
+            | CharacterError::Context { .. }
+            | CharacterError::Line { .. }
+            | CharacterError::Unexpected { .. }
+            | CharacterError::Unterminated { .. } => false,
+            #[cfg(feature = "regex-automata")]
+            CharacterError::Regex(_) => false,
+        }
+    }
+
+    /// Create a copy of this error.
+    ///
+    /// IO errors and other errors can't be cloned, so they are recreated from their kind and message.
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            CharacterError::NoBytesRead => CharacterError::NoBytesRead,
+            CharacterError::IoError { bytes, error } => CharacterError::IoError {
+                bytes: bytes.clone(),
+                error: io::Error::new(error.kind(), error.to_string()),
+            },
+            CharacterError::Other { bytes, error } => CharacterError::Other {
+                bytes: bytes.clone(),
+                error: anyhow::anyhow!("{:#}", error),
+            },
+            CharacterError::InvalidStartByte(byte) => CharacterError::InvalidStartByte(*byte),
+            CharacterError::UnexpectedContinuation(byte) => {
+                CharacterError::UnexpectedContinuation(*byte)
+            }
+            CharacterError::TruncatedSequence {
+                bytes,
+                expected,
+                got,
+            } => CharacterError::TruncatedSequence {
+                bytes: bytes.clone(),
+                expected: *expected,
+                got: *got,
+            },
+            CharacterError::InvalidSequence(bytes) => {
+                CharacterError::InvalidSequence(bytes.clone())
+            }
+            CharacterError::Context { label, source } => CharacterError::Context {
+                label,
+                source: Box::new(source.duplicate()),
+            },
+            CharacterError::Line { line, source } => CharacterError::Line {
+                line: *line,
+                source: Box::new(source.duplicate()),
+            },
+            #[cfg(feature = "regex-automata")]
+            CharacterError::Regex(error) => CharacterError::Regex(error.clone()),
+            CharacterError::Utf8 {
+                bytes,
+                valid_up_to,
+                error_len,
+            } => CharacterError::Utf8 {
+                bytes: bytes.clone(),
+                valid_up_to: *valid_up_to,
+                error_len: *error_len,
+            },
+            CharacterError::Surrogate { bytes, code_point } => CharacterError::Surrogate {
+                bytes: bytes.clone(),
+                code_point: *code_point,
+            },
+            CharacterError::Overlong { bytes } => CharacterError::Overlong {
+                bytes: bytes.clone(),
+            },
+            CharacterError::OutOfRange { bytes, code_point } => CharacterError::OutOfRange {
+                bytes: bytes.clone(),
+                code_point: *code_point,
+            },
+            CharacterError::Rejected {
+                bytes,
+                character,
+                reason,
+            } => CharacterError::Rejected {
+                bytes: bytes.clone(),
+                character: *character,
+                reason,
+            },
+            CharacterError::PeekLimitExceeded(max) => CharacterError::PeekLimitExceeded(*max),
+            CharacterError::WouldBlock => CharacterError::WouldBlock,
+            CharacterError::TooManyInterrupts(count) => CharacterError::TooManyInterrupts(*count),
+            CharacterError::Unexpected {
+                expected,
+                found,
+                position,
+            } => CharacterError::Unexpected {
+                expected: expected.clone(),
+                found: *found,
+                position: *position,
+            },
+            CharacterError::Unterminated { what, start } => CharacterError::Unterminated {
+                what,
+                start: *start,
+            },
+        }
+    }
+
     pub fn bytes(&self) -> Option<&[u8]> {
         match self {
-            CharacterError::NoBytesRead => None,
+            CharacterError::NoBytesRead
+            | CharacterError::PeekLimitExceeded(_)
+            | CharacterError::WouldBlock
+            | CharacterError::TooManyInterrupts(_)
+            | CharacterError::Unexpected { .. }
+            | CharacterError::Unterminated { .. } => None,
+            #[cfg(feature = "regex-automata")]
+            CharacterError::Regex(_) => None,
+            CharacterError::Context { source, .. } | CharacterError::Line { source, .. } => {
+                source.bytes()
+            }
+            CharacterError::InvalidStartByte(byte)
+            | CharacterError::UnexpectedContinuation(byte) => Some(std::slice::from_ref(byte)),
             CharacterError::Other { bytes, error: _ }
-            | CharacterError::IoError { bytes, error: _ } => Some(&bytes),
+            | CharacterError::IoError { bytes, error: _ }
+            | CharacterError::TruncatedSequence { bytes, .. }
+            | CharacterError::InvalidSequence(bytes)
+            | CharacterError::Utf8 { bytes, .. }
+            | CharacterError::Surrogate { bytes, .. }
+            | CharacterError::Overlong { bytes }
+            | CharacterError::OutOfRange { bytes, .. }
+            | CharacterError::Rejected { bytes, .. } => Some(bytes),
+        }
+    }
+}
+
+impl From<io::Error> for CharacterError {
+    fn from(error: io::Error) -> Self {
+        CharacterError::IoError {
+            bytes: vec![],
+            error,
         }
     }
 }
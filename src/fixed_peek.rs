@@ -0,0 +1,147 @@
+use crate::{
+    CharStream, CharacterError, CharacterIterator, CharacterStreamResult, PeekCharStream,
+    INTERRUPTED_MAX,
+};
+
+/// A peekable stream that can look up to `N` characters ahead.
+///
+/// The lookahead window is stored inline, so no heap allocation is made for peeking.
+pub struct FixedPeekableCharacterStream<Stream: CharStream, const N: usize> {
+    /// The stream the characters are read from.
+    pub stream: Stream,
+    /// Ring buffer of peeked results, starting at `start`.
+    window: [Option<CharacterStreamResult>; N],
+    start: usize,
+    len: usize,
+}
+
+impl<Stream: CharStream, const N: usize> FixedPeekableCharacterStream<Stream, N> {
+    /// Create a [FixedPeekableCharacterStream] from a stream, with nothing peeked yet.
+    pub fn new(stream: Stream) -> Self {
+        Self {
+            stream,
+            window: std::array::from_fn(|_| None),
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Peek the character `K` positions ahead, where 0 is the next character.
+    ///
+    /// `K` is checked to be smaller than `N` at compile time.
+    pub fn peek<const K: usize>(&mut self) -> Option<&CharacterStreamResult> {
+        const { assert!(K < N, "peek offset must be smaller than the window size") };
+        self.peek_nth(K)
+    }
+
+    /// Peek the character `n` positions ahead, where 0 is the next character.
+    ///
+    /// Returns `None` if the stream ends before that character, or if `n` doesn't fit in the window.
+    pub fn peek_nth(&mut self, n: usize) -> Option<&CharacterStreamResult> {
+        if n >= N {
+            return None;
+        }
+
+        while self.len <= n {
+            match self.stream.read_char() {
+                Err(CharacterError::NoBytesRead) => return None,
+                result => {
+                    self.window[(self.start + self.len) % N] = Some(result);
+                    self.len += 1;
+                }
+            }
+        }
+
+        self.window[(self.start + n) % N].as_ref()
+    }
+
+    /// The amount of characters currently peeked.
+    pub fn peeked(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the underlying stream along with the peeked results that haven't been read yet,
+    /// in the order they would have been read.
+    pub fn into_inner(mut self) -> (Stream, Vec<CharacterStreamResult>) {
+        let peeked = (0..self.len)
+            .filter_map(|i| self.window[(self.start + i) % N].take())
+            .collect();
+
+        (self.stream, peeked)
+    }
+}
+
+impl<Stream: CharStream, const N: usize> CharStream for FixedPeekableCharacterStream<Stream, N> {
+    fn read_char(&mut self) -> CharacterStreamResult {
+        if self.len == 0 {
+            return self.stream.read_char();
+        }
+
+        let result = self.window[self.start].take();
+        self.start = (self.start + 1) % N;
+        self.len -= 1;
+
+        result.unwrap_or(Err(CharacterError::NoBytesRead))
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.stream.is_lossy()
+    }
+}
+
+impl<Stream: CharStream, const N: usize> PeekCharStream
+    for FixedPeekableCharacterStream<Stream, N>
+{
+    fn peek_nth(&mut self, n: usize) -> Option<&CharacterStreamResult> {
+        FixedPeekableCharacterStream::peek_nth(self, n)
+    }
+}
+
+impl<Stream: CharStream, const N: usize> From<Stream> for FixedPeekableCharacterStream<Stream, N> {
+    fn from(stream: Stream) -> Self {
+        Self::new(stream)
+    }
+}
+
+impl<Stream: CharStream, const N: usize> IntoIterator for FixedPeekableCharacterStream<Stream, N> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CharStream, ToCharacterStream};
+
+    #[test]
+    fn fixed_peek_test() {
+        let mut character_stream = b"ab\xFFcd".to_character_stream().peeky_fixed::<2>();
+
+        assert_eq!(
+            character_stream.peek::<1>().unwrap().as_ref().unwrap(),
+            &'b'
+        );
+        assert_eq!(
+            character_stream.peek::<0>().unwrap().as_ref().unwrap(),
+            &'a'
+        );
+        assert!(character_stream.peek_nth(2).is_none());
+        assert_eq!(character_stream.read_char().unwrap(), 'a');
+        assert!(character_stream.peek::<1>().unwrap().is_err());
+        assert_eq!(character_stream.read_char().unwrap(), 'b');
+        assert!(character_stream.read_char().is_err());
+        assert_eq!(
+            character_stream.peek::<1>().unwrap().as_ref().unwrap(),
+            &'d'
+        );
+        assert_eq!(character_stream.read_char().unwrap(), 'c');
+        assert!(character_stream.peek::<1>().is_none());
+        assert_eq!(character_stream.peeked(), 1);
+        assert_eq!(character_stream.read_char().unwrap(), 'd');
+        assert!(character_stream.read_char().is_err());
+    }
+}
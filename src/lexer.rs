@@ -0,0 +1,253 @@
+use std::ops::Range;
+
+use crate::{CharacterError, PeekCharStream, Position};
+
+/// Peeks at the upcoming characters and returns how many of them a rule matches.
+type Matcher = Box<dyn FnMut(&mut dyn PeekCharStream) -> usize>;
+
+/// A token produced by a [Lexer].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token<Kind> {
+    /// The kind given to the rule that matched the token.
+    pub kind: Kind,
+    /// The characters that make up the token.
+    pub text: String,
+    /// The character offsets the token covers.
+    pub span: Range<usize>,
+    /// The position of the first character of the token.
+    pub position: Position,
+}
+
+/// A rule-based tokenizer over any [PeekCharStream].
+///
+/// At every point the rule matching the most characters wins, ties go to the rule registered first.
+/// Tokens are produced by iterating over the lexer.
+pub struct Lexer<Stream: PeekCharStream, Kind: Clone> {
+    /// The stream the characters are read from.
+    pub stream: Stream,
+    /// The rules, along with the kind of token they produce, or `None` if the match is skipped.
+    rules: Vec<(Option<Kind>, Matcher)>,
+    /// The character offset of the next character.
+    offset: usize,
+    /// The position of the next character.
+    position: Position,
+}
+
+impl<Stream: PeekCharStream, Kind: Clone> Lexer<Stream, Kind> {
+    /// Create a [Lexer] reading from `stream`, without any rules.
+    pub fn new(stream: Stream) -> Self {
+        Self {
+            stream,
+            rules: vec![],
+            offset: 0,
+            position: Position::default(),
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Produce a `kind` token for `literal`.
+    pub fn literal(self, kind: Kind, literal: &str) -> Self {
+        self.push_rule(Some(kind), literal_matcher(literal))
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Produce a `kind` token for every run of one or more characters satisfying `predicate`.
+    pub fn run(self, kind: Kind, predicate: impl Fn(char) -> bool + 'static) -> Self {
+        self.push_rule(Some(kind), run_matcher(predicate))
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Produce a `kind` token for the amount of characters `matcher` returns.
+    /// `matcher` should only peek at the stream; the lexer consumes the characters.
+    pub fn rule(
+        self,
+        kind: Kind,
+        matcher: impl FnMut(&mut dyn PeekCharStream) -> usize + 'static,
+    ) -> Self {
+        self.push_rule(Some(kind), Box::new(matcher))
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Skip runs of characters satisfying `predicate`, like whitespace, without producing tokens.
+    pub fn skip(self, predicate: impl Fn(char) -> bool + 'static) -> Self {
+        self.push_rule(None, run_matcher(predicate))
+    }
+
+    /// The position of the next character.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Return the underlying stream.
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+
+    fn push_rule(mut self, kind: Option<Kind>, matcher: Matcher) -> Self {
+        self.rules.push((kind, matcher));
+        self
+    }
+
+    /// Reads the next token, skipping anything matched by a skip rule.
+    ///
+    /// Returns `Ok(None)` at the end of the stream. If no rule matches, the offending character is
+    /// consumed and returned in a [CharacterError::Unexpected].
+    pub fn next_token(&mut self) -> Result<Option<Token<Kind>>, CharacterError> {
+        loop {
+            match self.stream.peek_nth(0) {
+                None => return Ok(None),
+                Some(Err(_)) => return Err(self.stream.read_char().unwrap_err()),
+                Some(Ok(_)) => {}
+            }
+
+            let mut best: Option<(usize, usize)> = None;
+            for (index, (_, matcher)) in self.rules.iter_mut().enumerate() {
+                let length = matcher(&mut self.stream);
+                if length > best.map_or(0, |(_, best_length)| best_length) {
+                    best = Some((index, length));
+                }
+            }
+
+            let start = self.offset;
+            let position = self.position;
+
+            let Some((index, length)) = best else {
+                let found = self.advance()?;
+                return Err(CharacterError::Unexpected {
+                    expected: "a token".to_string(),
+                    found: Some(found),
+                    position: Some(position),
+                });
+            };
+
+            let mut text = String::new();
+            for _ in 0..length {
+                text.push(self.advance()?);
+            }
+
+            if let Some(kind) = &self.rules[index].0 {
+                return Ok(Some(Token {
+                    kind: kind.clone(),
+                    text,
+                    span: start..self.offset,
+                    position,
+                }));
+            }
+        }
+    }
+
+    fn advance(&mut self) -> Result<char, CharacterError> {
+        let character = self.stream.read_char()?;
+        self.offset += 1;
+        self.position.advance(character, 1);
+
+        Ok(character)
+    }
+}
+
+impl<Stream: PeekCharStream, Kind: Clone> Iterator for Lexer<Stream, Kind> {
+    type Item = Result<Token<Kind>, CharacterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token().transpose()
+    }
+}
+
+fn literal_matcher(literal: &str) -> Matcher {
+    let literal: Vec<char> = literal.chars().collect();
+
+    Box::new(move |stream| {
+        let matches = literal.iter().enumerate().all(
+            |(n, expected)| matches!(stream.peek_nth(n), Some(Ok(character)) if character == expected),
+        );
+
+        if matches {
+            literal.len()
+        } else {
+            0
+        }
+    })
+}
+
+fn run_matcher(predicate: impl Fn(char) -> bool + 'static) -> Matcher {
+    Box::new(move |stream| {
+        let mut length = 0;
+        while matches!(stream.peek_nth(length), Some(Ok(character)) if predicate(*character)) {
+            length += 1;
+        }
+        length
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToCharacterStream;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        Let,
+        Identifier,
+        Number,
+        Equals,
+        EqualsEquals,
+    }
+
+    #[test]
+    fn lexer_test() {
+        let stream = "let letter = 42\n== ?".to_character_stream().peeky();
+        let mut lexer = Lexer::new(stream)
+            .skip(char::is_whitespace)
+            .literal(Kind::Let, "let")
+            .run(Kind::Identifier, char::is_alphabetic)
+            .literal(Kind::Equals, "=")
+            .literal(Kind::EqualsEquals, "==")
+            .rule(Kind::Number, |stream| {
+                let mut length = 0;
+                while matches!(stream.peek_nth(length), Some(Ok('0'..='9'))) {
+                    length += 1;
+                }
+                length
+            });
+
+        let tokens: Vec<_> = lexer.by_ref().map_while(Result::ok).collect();
+        let kinds: Vec<_> = tokens.iter().map(|token| token.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                Kind::Let,
+                Kind::Identifier,
+                Kind::Equals,
+                Kind::Number,
+                Kind::EqualsEquals
+            ]
+        );
+        assert_eq!(tokens[1].text, "letter");
+        assert_eq!(tokens[1].span, 4..10);
+        assert_eq!(tokens[4].position, Position { line: 2, column: 1 });
+
+        assert!(lexer.next().is_none());
+    }
+
+    #[test]
+    fn lexer_unexpected_test() {
+        let stream = "a?b".to_character_stream().peeky();
+        let mut lexer = Lexer::new(stream).run("identifier", char::is_alphabetic);
+
+        assert_eq!(lexer.next().unwrap().unwrap().text, "a");
+        assert!(matches!(
+            lexer.next(),
+            Some(Err(CharacterError::Unexpected {
+                found: Some('?'),
+                position: Some(Position { line: 1, column: 2 }),
+                ..
+            }))
+        ));
+        assert_eq!(lexer.next().unwrap().unwrap().text, "b");
+        assert!(lexer.next().is_none());
+    }
+}
@@ -1,10 +1,62 @@
+mod adapters;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod async_stream;
+mod buf_read_stream;
+mod cesu8;
 mod character_iter;
 mod character_stream;
+mod checkpoint;
+#[cfg(feature = "tokio-util")]
+mod codec;
+mod decoder;
+mod detect;
+#[cfg(feature = "encoding_rs")]
+mod encoding;
 mod error;
+mod fixed_peek;
+mod lexer;
+mod lines;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod position;
+mod push_decoder;
+#[cfg(feature = "regex-automata")]
+mod regex;
+mod scan;
+mod single_byte;
+mod transcode;
+mod utf16;
+mod utf32;
 
 pub use crate::character_stream::*;
+pub use adapters::*;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub use async_stream::*;
+pub use buf_read_stream::*;
+pub use cesu8::*;
 pub use character_iter::*;
+pub use checkpoint::*;
+#[cfg(feature = "tokio-util")]
+pub use codec::*;
+pub use decoder::*;
+pub use detect::*;
+#[cfg(feature = "encoding_rs")]
+pub use encoding::*;
 pub use error::*;
+pub use fixed_peek::*;
+pub use lexer::*;
+pub use lines::*;
+#[cfg(feature = "rayon")]
+pub use parallel::*;
+pub use position::*;
+pub use push_decoder::*;
+#[cfg(feature = "regex-automata")]
+pub use regex::*;
+pub use scan::*;
+pub use single_byte::*;
+pub use transcode::*;
+pub use utf16::*;
+pub use utf32::*;
 
 pub struct Peek;
 pub struct MultiPeek;
@@ -0,0 +1,205 @@
+use std::io::Read;
+
+use crate::{CharStream, CharacterError, PeekableCharacterStream};
+
+/// The terminator that ended a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// A line feed, `\n`.
+    Lf,
+    /// A carriage return followed by a line feed, `\r\n`.
+    CrLf,
+    /// A lone carriage return, `\r`.
+    Cr,
+    /// The line was ended by the end of the stream.
+    None,
+}
+
+impl LineEnding {
+    /// The characters that make up the line ending.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+            LineEnding::None => "",
+        }
+    }
+}
+
+/// What a character means to the line being read, see [LineSplitter::feed].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LineStep {
+    /// A character of the line.
+    Char(char),
+    /// The line ends here. A `\r` is reported as [LineEnding::Cr], readers that can peek upgrade it
+    /// to [LineEnding::CrLf] themselves.
+    End(LineEnding),
+    /// The `\n` of a `\r\n` that already ended the previous line.
+    Skip,
+}
+
+/// The line terminator logic shared by every line reader: `\n`, `\r\n` and a lone `\r` all end a
+/// line.
+///
+/// Readers that can't peek keep one around between lines, so the `\n` of a `\r\n` split across
+/// two reads is skipped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LineSplitter {
+    /// Whether the previous line ended with a `\r`.
+    after_cr: bool,
+}
+
+impl LineSplitter {
+    pub(crate) fn feed(&mut self, character: char) -> LineStep {
+        match (std::mem::take(&mut self.after_cr), character) {
+            (true, '\n') => LineStep::Skip,
+            (_, '\n') => LineStep::End(LineEnding::Lf),
+            (_, '\r') => {
+                self.after_cr = true;
+                LineStep::End(LineEnding::Cr)
+            }
+            (_, character) => LineStep::Char(character),
+        }
+    }
+}
+
+impl<Reader: Read, PI, Stream: CharStream> PeekableCharacterStream<Reader, PI, Stream>
+where
+    Self: CharStream,
+{
+    /// Reads a line from the stream, along with the [LineEnding] that terminated it.
+    ///
+    /// The line ending is not included in the returned string.
+    ///
+    /// Returns `Ok(None)` if the stream has ended.
+    pub fn read_line_with_ending(
+        &mut self,
+    ) -> Result<Option<(String, LineEnding)>, CharacterError> {
+        let mut line = String::new();
+        let mut splitter = LineSplitter::default();
+
+        loop {
+            let Some(character) = self.read_char_or_eof()? else {
+                return Ok((!line.is_empty()).then_some((line, LineEnding::None)));
+            };
+
+            match splitter.feed(character) {
+                LineStep::Char(character) => line.push(character),
+                LineStep::End(LineEnding::Cr) => {
+                    let ending = match self.next_if_eq('\n') {
+                        Some(Ok(_)) => LineEnding::CrLf,
+                        Some(error) => {
+                            self.unread_result(error);
+                            LineEnding::Cr
+                        }
+                        None => LineEnding::Cr,
+                    };
+
+                    return Ok(Some((line, ending)));
+                }
+                LineStep::End(ending) => return Ok(Some((line, ending))),
+                LineStep::Skip => {}
+            }
+        }
+    }
+
+    /// Reads a line from the stream, ended by `\n`, `\r\n` or a lone `\r`.
+    ///
+    /// The line ending is not included in the returned string.
+    ///
+    /// Returns `Ok(None)` if the stream has ended.
+    pub fn read_line(&mut self) -> Result<Option<String>, CharacterError> {
+        Ok(self.read_line_with_ending()?.map(|(line, _)| line))
+    }
+
+    /// Reads a line from the stream, keeping the line ending at the end of the returned string.
+    ///
+    /// Returns `Ok(None)` if the stream has ended.
+    pub fn read_line_inclusive(&mut self) -> Result<Option<String>, CharacterError> {
+        Ok(self.read_line_with_ending()?.map(|(mut line, ending)| {
+            line.push_str(ending.as_str());
+            line
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToCharacterStream;
+
+    #[test]
+    fn read_line_with_ending_test() {
+        let mut character_stream = "lf\ncrlf\r\ncr\rlast".to_character_stream().peeky();
+        let mut lines = vec![];
+
+        while let Some(line) = character_stream.read_line_with_ending().unwrap() {
+            lines.push(line);
+        }
+
+        assert_eq!(
+            lines,
+            [
+                ("lf".to_string(), LineEnding::Lf),
+                ("crlf".to_string(), LineEnding::CrLf),
+                ("cr".to_string(), LineEnding::Cr),
+                ("last".to_string(), LineEnding::None),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_line_test() {
+        let mut character_stream = b"a\r\nb\xFF\nc".to_character_stream_lossy().peeky();
+
+        assert_eq!(
+            character_stream.read_line_inclusive().unwrap().unwrap(),
+            "a\r\n"
+        );
+        assert_eq!(character_stream.read_line().unwrap().unwrap(), "b\u{FFFD}");
+        assert_eq!(
+            character_stream.read_line_inclusive().unwrap().unwrap(),
+            "c"
+        );
+        assert!(character_stream.read_line().unwrap().is_none());
+    }
+
+    #[test]
+    fn line_splitter_test() {
+        let mut splitter = LineSplitter::default();
+        let steps: Vec<_> = "a\r\n\n\r\rb".chars().map(|c| splitter.feed(c)).collect();
+
+        assert_eq!(
+            steps,
+            [
+                LineStep::Char('a'),
+                LineStep::End(LineEnding::Cr),
+                LineStep::Skip,
+                LineStep::End(LineEnding::Lf),
+                LineStep::End(LineEnding::Cr),
+                LineStep::End(LineEnding::Cr),
+                LineStep::Char('b'),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_line_position_test() {
+        let mut character_stream = "a\rb\r\nc".to_character_stream().peeky();
+
+        assert_eq!(character_stream.read_line().unwrap().unwrap(), "a");
+        assert_eq!(character_stream.chars_read(), 2);
+        assert_eq!(
+            character_stream.read_line_inclusive().unwrap().unwrap(),
+            "b\r\n"
+        );
+        assert_eq!(character_stream.chars_read(), 5);
+        assert_eq!(character_stream.read_line().unwrap().unwrap(), "c");
+        assert_eq!(character_stream.chars_read(), 6);
+
+        let mut character_stream = b"a\r\xFF".to_character_stream().peeky();
+        assert_eq!(character_stream.read_line().unwrap().unwrap(), "a");
+        assert!(character_stream.read_line().is_err());
+    }
+}
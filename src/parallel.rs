@@ -0,0 +1,86 @@
+use std::io::Cursor;
+
+use rayon::prelude::*;
+
+use crate::{CharacterError, CharacterStream};
+
+/// The smallest amount of bytes worth decoding on a separate thread.
+const MIN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Move `index` backwards to the nearest byte that starts a character.
+///
+/// Only the length of the longest UTF-8 sequence is scanned, so invalid input still gets split.
+fn char_boundary(bytes: &[u8], index: usize) -> usize {
+    (index.saturating_sub(3)..=index)
+        .rev()
+        .find(|&i| i == bytes.len() || (bytes[i] & 0xC0) != 0x80)
+        .unwrap_or(index)
+}
+
+/// Decode `bytes` into characters, splitting the work across the [rayon] thread pool.
+///
+/// The bytes are only ever split on character boundaries, so the result is identical to decoding them sequentially.
+pub fn decode_parallel(bytes: &[u8], lossy: bool) -> Result<Vec<char>, CharacterError> {
+    let chunk_size = (bytes.len() / rayon::current_num_threads()).max(MIN_CHUNK_SIZE);
+    let mut chunks = vec![];
+    let mut start = 0;
+
+    while start < bytes.len() {
+        let end = if start + chunk_size >= bytes.len() {
+            bytes.len()
+        } else {
+            let end = char_boundary(bytes, start + chunk_size);
+            if end > start {
+                end
+            } else {
+                start + chunk_size
+            }
+        };
+
+        chunks.push(&bytes[start..end]);
+        start = end;
+    }
+
+    // Every chunk's result is kept in order, so the first error is the one a sequential decode
+    // would have hit, not whichever chunk failed first.
+    let results: Vec<Result<Vec<char>, CharacterError>> = chunks
+        .into_par_iter()
+        .map(|chunk| {
+            CharacterStream::new(Cursor::new(chunk), lossy)
+                .into_iter()
+                .collect()
+        })
+        .collect();
+    let decoded = results.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+    Ok(decoded.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToCharacterIterator;
+
+    #[test]
+    fn decode_parallel_test() {
+        let text = "ascii \u{1F4BB} \u{E9}\u{20AC}\n".repeat(200_000);
+        let sequential: Vec<char> = text
+            .to_character_iterator()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(decode_parallel(text.as_bytes(), false).unwrap(), sequential);
+    }
+
+    #[test]
+    fn first_error_test() {
+        let mut bytes = b"a".repeat(4 * MIN_CHUNK_SIZE);
+        bytes[10] = 0xFF;
+        bytes[3 * MIN_CHUNK_SIZE] = 0xFE;
+
+        for _ in 0..10 {
+            let error = decode_parallel(&bytes, false).unwrap_err();
+            assert_eq!(error.bytes(), Some(&b"\xFF"[..]));
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    CharStream, CharacterIterator, CharacterStreamResult, PeekCharStream, INTERRUPTED_MAX,
+};
+
+/// A line and column in a stream of characters, both starting at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    /// The line, which is advanced by every `\n`.
+    pub line: usize,
+    /// The column, counted in characters.
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self { line: 1, column: 1 }
+    }
+}
+
+impl Position {
+    /// Move past `character`.
+    ///
+    /// A tab moves to the next tab stop, which is every `tab_width` columns.
+    /// With a `tab_width` of 1, a tab counts as a single column.
+    pub fn advance(&mut self, character: char, tab_width: usize) {
+        match character {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            '\t' if tab_width > 1 => {
+                self.column = ((self.column - 1) / tab_width + 1) * tab_width + 1;
+            }
+            _ => self.column += 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A [Position] together with the file and byte offset it belongs to, for use in diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    /// The file the stream is read from, if known.
+    pub file: Option<PathBuf>,
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column, starting at 1.
+    pub column: usize,
+    /// The byte offset, if the underlying stream keeps track of it.
+    pub byte_offset: Option<usize>,
+}
+
+impl Location {
+    /// The line and column of this location.
+    pub fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Wrapper that tracks the [Position] of the next character read from a [CharStream].
+pub struct PositionedCharacterStream<Stream: CharStream> {
+    /// The stream the characters are read from.
+    pub stream: Stream,
+    /// The position of the next character.
+    pub(crate) position: Position,
+    /// The distance between tab stops, in columns.
+    pub tab_width: usize,
+    /// The file the stream is read from, reported in every [Location].
+    pub file: Option<PathBuf>,
+}
+
+impl<Stream: CharStream> PositionedCharacterStream<Stream> {
+    /// Create a [PositionedCharacterStream] starting at line 1, column 1.
+    pub fn new(stream: Stream) -> Self {
+        Self {
+            stream,
+            position: Position::default(),
+            tab_width: 1,
+            file: None,
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Place tab stops every `tab_width` columns. A `tab_width` of 1 counts a tab as a single column.
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// Report `file` as the origin of every [Location].
+    pub fn file(mut self, file: impl AsRef<Path>) -> Self {
+        self.file = Some(file.as_ref().to_path_buf());
+        self
+    }
+
+    /// The position of the next character.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The location of the next character.
+    pub fn location(&self) -> Location {
+        Location {
+            file: self.file.clone(),
+            line: self.position.line,
+            column: self.position.column,
+            byte_offset: self.stream.bytes_read(),
+        }
+    }
+
+    /// Return the underlying stream.
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+}
+
+impl<Stream: CharStream> CharStream for PositionedCharacterStream<Stream> {
+    fn read_char(&mut self) -> CharacterStreamResult {
+        let character = self.stream.read_char()?;
+        self.position.advance(character, self.tab_width);
+
+        Ok(character)
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.stream.is_lossy()
+    }
+
+    fn bytes_read(&self) -> Option<usize> {
+        self.stream.bytes_read()
+    }
+
+    fn bytes_remaining(&self) -> Option<usize> {
+        self.stream.bytes_remaining()
+    }
+
+    fn current_position(&self) -> Option<Position> {
+        Some(self.position)
+    }
+}
+
+impl<Stream: PeekCharStream> PeekCharStream for PositionedCharacterStream<Stream> {
+    fn peek_nth(&mut self, n: usize) -> Option<&CharacterStreamResult> {
+        self.stream.peek_nth(n)
+    }
+}
+
+impl<Stream: CharStream + std::fmt::Debug> std::fmt::Debug for PositionedCharacterStream<Stream> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PositionedCharacterStream")
+            .field("stream", &self.stream)
+            .field("position", &self.position)
+            .field("tab_width", &self.tab_width)
+            .field("file", &self.file)
+            .finish()
+    }
+}
+
+impl<Stream: CharStream> IntoIterator for PositionedCharacterStream<Stream> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharacterError, ToCharacterStream};
+
+    #[test]
+    fn position_test() {
+        let mut character_stream = "ab\n\u{1F4BB}c".to_character_stream().positioned();
+        let mut positions = vec![character_stream.position()];
+
+        while character_stream.read_char_or_eof().unwrap().is_some() {
+            positions.push(character_stream.position());
+        }
+
+        let expected: Vec<Position> = [(1, 1), (1, 2), (1, 3), (2, 1), (2, 2), (2, 3)]
+            .into_iter()
+            .map(|(line, column)| Position { line, column })
+            .collect();
+        assert_eq!(positions, expected);
+    }
+
+    #[test]
+    fn tab_width_test() {
+        let columns = |tab_width| {
+            let mut character_stream = "\ta\tb\t"
+                .to_character_stream()
+                .positioned()
+                .tab_width(tab_width);
+            let mut columns = vec![];
+
+            while character_stream.read_char_or_eof().unwrap().is_some() {
+                columns.push(character_stream.position().column);
+            }
+
+            columns
+        };
+
+        assert_eq!(columns(1), [2, 3, 4, 5, 6]);
+        assert_eq!(columns(4), [5, 6, 9, 10, 13]);
+    }
+
+    #[test]
+    fn location_test() {
+        let mut character_stream = "\u{1F4BB}\nab"
+            .to_character_stream()
+            .positioned()
+            .file("src/main.txt");
+
+        character_stream.read_char().unwrap();
+        character_stream.read_char().unwrap();
+        character_stream.read_char().unwrap();
+
+        let location = character_stream.location();
+        assert_eq!(
+            location,
+            Location {
+                file: Some(PathBuf::from("src/main.txt")),
+                line: 2,
+                column: 2,
+                byte_offset: Some(6),
+            }
+        );
+        assert_eq!(location.to_string(), "src/main.txt:2:2");
+    }
+
+    #[test]
+    fn expect_test() {
+        let stream = "fn\n  main".to_character_stream().peeky();
+        let mut character_stream = PositionedCharacterStream::new(stream);
+
+        character_stream.expect_str("fn").unwrap();
+        character_stream.expect('\n').unwrap();
+        character_stream.skip_whitespace();
+
+        let error = character_stream.expect_str("mian").unwrap_err();
+        assert!(matches!(
+            error,
+            CharacterError::Unexpected {
+                found: Some('a'),
+                position: Some(Position { line: 2, column: 3 }),
+                ..
+            }
+        ));
+        assert_eq!(error.to_string(), "Expected \"mian\", found 'a' at 2:3.");
+
+        character_stream.expect_str("main").unwrap();
+        assert!(matches!(
+            character_stream.expect(';'),
+            Err(CharacterError::Unexpected { found: None, .. })
+        ));
+
+        let mut character_stream = b"a\xFFb".to_character_stream().peeky();
+        assert!(character_stream.expect_str("ab").is_err());
+        character_stream.expect('b').unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use crate::{CharacterStreamResult, Decoded, Utf8Decoder};
+
+/// UTF-8 decoder that owns no reader, bytes are pushed into it instead.
+///
+/// Useful when the bytes arrive in buffers owned by someone else, such as a network server or a
+/// WASM host.
+#[derive(Debug, Clone, Default)]
+pub struct PushDecoder {
+    /// Whether or not we should care whether invalid bytes are detected.
+    ///
+    /// If `true`, then invalid byte sequences will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+    /// See [CharacterStream::strict](crate::CharacterStream::strict).
+    pub strict: bool,
+    /// Bytes that have been fed but not decoded yet.
+    buffer: VecDeque<u8>,
+}
+
+impl PushDecoder {
+    /// Create a [PushDecoder].
+    pub fn new(is_lossy: bool) -> Self {
+        Self {
+            is_lossy,
+            ..Default::default()
+        }
+    }
+
+    /// Kinda builder pattern.
+    ///
+    /// See [CharacterStream::strict](crate::CharacterStream::strict).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Append `bytes` to the input.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend(bytes);
+    }
+
+    /// Amount of bytes that have been fed but not decoded yet.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Decode the next character.
+    ///
+    /// Returns `None` if more bytes need to be fed first.
+    pub fn next_char(&mut self) -> Option<CharacterStreamResult> {
+        let mut bytes = [0u8; 4];
+        let len = self.buffer.len().min(bytes.len());
+        for (slot, byte) in bytes.iter_mut().zip(&self.buffer) {
+            *slot = *byte;
+        }
+
+        let (result, used) = match self.decoder().decode(&bytes[..len]) {
+            Decoded::Char(character, used) => (Ok(character), used),
+            Decoded::Invalid(error, used) => (Err(error), used),
+            Decoded::Incomplete => return None,
+        };
+        self.buffer.drain(..used);
+
+        Some(result)
+    }
+
+    /// The [Utf8Decoder] with the settings of this decoder.
+    fn decoder(&self) -> Utf8Decoder {
+        Utf8Decoder::new(self.is_lossy).strict(self.strict)
+    }
+
+    /// Decode the next character, treating the input as ended.
+    ///
+    /// A character left incomplete is an error, or a U+FFFD if `is_lossy` is set.
+    pub fn finish(&mut self) -> Option<CharacterStreamResult> {
+        if let Some(result) = self.next_char() {
+            return Some(result);
+        }
+
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let bytes: Vec<u8> = self.buffer.drain(..).collect();
+        Some(self.decoder().decode_truncated(&bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CharacterError;
+
+    #[test]
+    fn push_decoder_test() {
+        let mut decoder = PushDecoder::new(false);
+        decoder.feed(b"a\xE2\x82");
+
+        assert_eq!(decoder.next_char().unwrap().unwrap(), 'a');
+        assert!(decoder.next_char().is_none());
+        assert_eq!(decoder.buffered(), 2);
+
+        decoder.feed(b"\xAC\xF0");
+        assert_eq!(decoder.next_char().unwrap().unwrap(), '\u{20AC}');
+        assert!(decoder.next_char().is_none());
+        assert!(matches!(
+            decoder.finish(),
+            Some(Err(CharacterError::TruncatedSequence { bytes, expected: 4, got: 1 })) if bytes == [0xF0]
+        ));
+        assert!(decoder.finish().is_none());
+    }
+
+    #[test]
+    fn invalid_prefix_test() {
+        let mut decoder = PushDecoder::new(false);
+        decoder.feed(b"\xE2AB\xF0\x9F");
+
+        assert!(matches!(
+            decoder.next_char(),
+            Some(Err(CharacterError::InvalidSequence(bytes))) if bytes == [0xE2]
+        ));
+        assert_eq!(decoder.next_char().unwrap().unwrap(), 'A');
+        assert_eq!(decoder.next_char().unwrap().unwrap(), 'B');
+        assert!(decoder.next_char().is_none());
+
+        decoder.feed(b"C");
+        assert!(matches!(
+            decoder.next_char(),
+            Some(Err(CharacterError::InvalidSequence(bytes))) if bytes == [0xF0, 0x9F]
+        ));
+        assert_eq!(decoder.next_char().unwrap().unwrap(), 'C');
+    }
+}
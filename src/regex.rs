@@ -0,0 +1,124 @@
+use std::{io::Read, ops::Range};
+
+use regex_automata::{
+    dfa::{Automaton, StartError},
+    util::start,
+    Anchored, MatchError,
+};
+
+use crate::{CharStream, CharacterError, PeekableCharacterStream};
+
+/// Text matched by [PeekableCharacterStream::read_match].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegexMatch {
+    /// The matched characters.
+    pub text: String,
+    /// The character offsets the match covers, counted like [chars_read](PeekableCharacterStream::chars_read).
+    pub span: Range<usize>,
+}
+
+impl<Reader: Read, PI, Stream: CharStream> PeekableCharacterStream<Reader, PI, Stream> {
+    /// Match `dfa` against the upcoming characters and consume the match, if there is one.
+    ///
+    /// The match is anchored at the next character. Characters are peeked until the DFA can't match
+    /// any further, so only as much of the stream as the pattern needs is buffered. A decode error
+    /// or the end of the stream ends the input the DFA sees.
+    ///
+    /// Returns `Ok(None)` without consuming anything if `dfa` doesn't match.
+    pub fn read_match<A: Automaton>(
+        &mut self,
+        dfa: &A,
+    ) -> Result<Option<RegexMatch>, CharacterError> {
+        let config = start::Config::new().anchored(Anchored::Yes);
+        let mut state = dfa.start_state(&config).map_err(start_error)?;
+        // The length in characters of the longest match so far.
+        let mut matched = None;
+        let mut length = 0;
+        // The length in bytes of the characters fed to the DFA.
+        let mut offset = 0;
+
+        'input: loop {
+            let character = match self.peek_nth(length) {
+                Some(Ok(character)) => *character,
+                _ => {
+                    state = dfa.next_eoi_state(state);
+                    if dfa.is_match_state(state) {
+                        matched = Some(length);
+                    }
+                    break;
+                }
+            };
+
+            let mut bytes = [0u8; 4];
+            for (i, byte) in character.encode_utf8(&mut bytes).bytes().enumerate() {
+                state = dfa.next_state(state, byte);
+
+                if dfa.is_special_state(state) {
+                    // Matches are reported one byte late, so this match ends before `character`.
+                    if dfa.is_match_state(state) && i == 0 {
+                        matched = Some(length);
+                    } else if dfa.is_dead_state(state) {
+                        break 'input;
+                    } else if dfa.is_quit_state(state) {
+                        return Err(CharacterError::Regex(MatchError::quit(byte, offset + i)));
+                    }
+                }
+            }
+
+            offset += character.len_utf8();
+            length += 1;
+        }
+
+        let Some(length) = matched else {
+            return Ok(None);
+        };
+
+        let start = self.chars_read;
+        let mut text = String::new();
+        for _ in 0..length {
+            text.push(self._read_char()?);
+        }
+
+        Ok(Some(RegexMatch {
+            text,
+            span: start..self.chars_read,
+        }))
+    }
+}
+
+fn start_error(error: StartError) -> CharacterError {
+    CharacterError::Regex(match error {
+        StartError::Quit { byte } => MatchError::quit(byte, 0),
+        StartError::UnsupportedAnchored { mode } => MatchError::unsupported_anchored(mode),
+        _ => MatchError::gave_up(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use regex_automata::dfa::dense;
+
+    use crate::{CharStream, ToCharacterStream};
+
+    #[test]
+    fn read_match_test() {
+        let number = dense::DFA::new(r"[0-9]+(\.[0-9]+)?").unwrap();
+        let word = dense::DFA::new(r"[a-zà-ÿ]+").unwrap();
+        let mut character_stream = "3.25.x héllo".to_character_stream().peeky();
+
+        let found = character_stream.read_match(&number).unwrap().unwrap();
+        assert_eq!(found.text, "3.25");
+        assert_eq!(found.span, 0..4);
+        assert!(character_stream.read_match(&number).unwrap().is_none());
+        assert_eq!(character_stream.read_char().unwrap(), '.');
+
+        assert_eq!(
+            character_stream.read_match(&word).unwrap().unwrap().text,
+            "x"
+        );
+        assert_eq!(character_stream.read_char().unwrap(), ' ');
+        let found = character_stream.read_match(&word).unwrap().unwrap();
+        assert_eq!(found.text, "héllo");
+        assert_eq!(found.span, 7..12);
+    }
+}
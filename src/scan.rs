@@ -0,0 +1,358 @@
+use crate::{CharacterError, PeekCharStream};
+
+/// The kind of a [NumberLiteral].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumberKind {
+    /// An integer without a prefix, like `1_000`.
+    Decimal,
+    /// An integer with a `0x` prefix.
+    Hexadecimal,
+    /// An integer with a `0o` prefix.
+    Octal,
+    /// An integer with a `0b` prefix.
+    Binary,
+    /// A number with a fraction or an exponent, like `1.5` or `2e-3`.
+    Float,
+}
+
+impl NumberKind {
+    /// The radix the digits of the literal are written in.
+    pub fn radix(&self) -> u32 {
+        match self {
+            NumberKind::Decimal | NumberKind::Float => 10,
+            NumberKind::Hexadecimal => 16,
+            NumberKind::Octal => 8,
+            NumberKind::Binary => 2,
+        }
+    }
+}
+
+/// A number literal read by [Scan::read_number].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumberLiteral {
+    /// The literal exactly as it was read, including sign, prefix and underscores.
+    pub text: String,
+    pub kind: NumberKind,
+}
+
+/// Scanners for common tokens, available on every [PeekCharStream].
+pub trait Scan: PeekCharStream + Sized {
+    /// Reads the next identifier, made of a character that is `XID_Start` or `_`, followed by any
+    /// amount of `XID_Continue` characters.
+    ///
+    /// Returns `Ok(None)` without consuming anything if the stream doesn't continue with an identifier.
+    #[cfg(feature = "unicode-ident")]
+    fn read_identifier(&mut self) -> Result<Option<String>, CharacterError> {
+        match self.peek_nth(0) {
+            Some(Ok(character)) if *character == '_' || unicode_ident::is_xid_start(*character) => {
+            }
+            _ => return Ok(None),
+        }
+
+        self.take_while(unicode_ident::is_xid_continue).map(Some)
+    }
+
+    /// Reads the next number literal.
+    ///
+    /// A literal is an optional sign followed by either a `0x`, `0o` or `0b` prefixed integer, or
+    /// a decimal number with an optional fraction and exponent. Digits may be separated by `_`.
+    /// A `.` is only part of the literal when a digit follows it, so `1..2` and `1.max(2)` stop after `1`.
+    ///
+    /// Returns `None` without consuming anything if the stream doesn't continue with a number.
+    fn read_number(&mut self) -> Option<NumberLiteral> {
+        let sign = peek_char(self, 0).is_some_and(|character| matches!(character, '+' | '-'));
+        let mut length = usize::from(sign);
+        if !is_digit(peek_char(self, length), 10) {
+            return None;
+        }
+
+        let prefixed = match (peek_char(self, length), peek_char(self, length + 1)) {
+            (Some('0'), Some('x' | 'X')) => Some(NumberKind::Hexadecimal),
+            (Some('0'), Some('o' | 'O')) => Some(NumberKind::Octal),
+            (Some('0'), Some('b' | 'B')) => Some(NumberKind::Binary),
+            _ => None,
+        };
+
+        let kind = match prefixed {
+            Some(kind) if digits_end(self, length + 2, kind.radix()) > length + 2 => {
+                length = digits_end(self, length + 2, kind.radix());
+                kind
+            }
+            _ => {
+                let mut kind = NumberKind::Decimal;
+                length = digits_end(self, length, 10);
+
+                if peek_char(self, length) == Some('.') && is_digit(peek_char(self, length + 1), 10)
+                {
+                    kind = NumberKind::Float;
+                    length = digits_end(self, length + 1, 10);
+                }
+
+                if matches!(peek_char(self, length), Some('e' | 'E')) {
+                    let sign = matches!(peek_char(self, length + 1), Some('+' | '-'));
+                    let digits = length + 1 + usize::from(sign);
+                    if is_digit(peek_char(self, digits), 10) {
+                        kind = NumberKind::Float;
+                        length = digits_end(self, digits, 10);
+                    }
+                }
+
+                kind
+            }
+        };
+
+        let mut text = String::with_capacity(length);
+        for _ in 0..length {
+            text.push(self.read_char().ok()?);
+        }
+
+        Some(NumberLiteral { text, kind })
+    }
+
+    /// Reads a literal enclosed in `quote`, processing escapes with [unescape_common].
+    ///
+    /// See [read_quoted_with](Scan::read_quoted_with).
+    fn read_quoted(&mut self, quote: char, escape: char) -> Result<Option<String>, CharacterError> {
+        self.read_quoted_with(quote, escape, unescape_common)
+    }
+
+    /// Reads a literal enclosed in `quote`, returning its contents without the quotes.
+    ///
+    /// Each `escape` character is dropped, and the character after it is replaced by what `unescape`
+    /// maps it to. If `unescape` returns `None`, [CharacterError::Unexpected] is returned.
+    ///
+    /// Returns `Ok(None)` without consuming anything if the stream doesn't continue with `quote`, and
+    /// [CharacterError::Unterminated] with the position of the opening quote if the stream ends first.
+    fn read_quoted_with(
+        &mut self,
+        quote: char,
+        escape: char,
+        mut unescape: impl FnMut(char) -> Option<char>,
+    ) -> Result<Option<String>, CharacterError> {
+        let start = self.current_position();
+        if peek_char(self, 0) != Some(quote) {
+            return Ok(None);
+        }
+        self.read_char()?;
+
+        let unterminated = || CharacterError::Unterminated {
+            what: "string",
+            start,
+        };
+        let mut text = String::new();
+
+        loop {
+            match self.read_char_or_eof()? {
+                Some(character) if character == escape => {
+                    let position = self.current_position();
+                    let escaped = self.read_char_or_eof()?.ok_or_else(unterminated)?;
+
+                    match unescape(escaped) {
+                        Some(character) => text.push(character),
+                        None => {
+                            return Err(CharacterError::Unexpected {
+                                expected: "an escape sequence".to_string(),
+                                found: Some(escaped),
+                                position,
+                            })
+                        }
+                    }
+                }
+                Some(character) if character == quote => return Ok(Some(text)),
+                Some(character) => text.push(character),
+                None => return Err(unterminated()),
+            }
+        }
+    }
+
+    /// Reads a region enclosed in `open` and `close`, returning its contents without the outer
+    /// delimiters. Nested pairs are kept in the returned text.
+    ///
+    /// Delimiters inside literals quoted with one of `quotes` are ignored, where a `\\` escapes the
+    /// next character. Pass an empty slice to not treat any character as a quote.
+    ///
+    /// Returns `Ok(None)` without consuming anything if the stream doesn't continue with `open`, and
+    /// [CharacterError::Unterminated] with the position of `open` if the stream ends first.
+    fn read_balanced(
+        &mut self,
+        open: char,
+        close: char,
+        quotes: &[char],
+    ) -> Result<Option<String>, CharacterError> {
+        let start = self.current_position();
+        if peek_char(self, 0) != Some(open) {
+            return Ok(None);
+        }
+        self.read_char()?;
+
+        let mut text = String::new();
+        let mut depth = 1;
+        let mut quoted: Option<char> = None;
+        let mut escaped = false;
+
+        while let Some(character) = self.read_char_or_eof()? {
+            match quoted {
+                Some(_) if escaped => escaped = false,
+                Some(_) if character == '\\' => escaped = true,
+                Some(quote) if character == quote => quoted = None,
+                Some(_) => {}
+                None if character == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(Some(text));
+                    }
+                }
+                None if character == open => depth += 1,
+                None if quotes.contains(&character) => quoted = Some(character),
+                None => {}
+            }
+
+            text.push(character);
+        }
+
+        Err(CharacterError::Unterminated {
+            what: "delimited region",
+            start,
+        })
+    }
+}
+
+/// Maps the character after an escape character to the character it stands for.
+///
+/// Handles `n`, `r`, `t`, `0`, `\\`, `'` and `"`, the escapes shared by most languages.
+pub fn unescape_common(character: char) -> Option<char> {
+    match character {
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        '0' => Some('\0'),
+        '\\' | '\'' | '"' => Some(character),
+        _ => None,
+    }
+}
+
+/// The character `n` positions ahead, if it decoded successfully.
+fn peek_char(stream: &mut impl PeekCharStream, n: usize) -> Option<char> {
+    match stream.peek_nth(n) {
+        Some(Ok(character)) => Some(*character),
+        _ => None,
+    }
+}
+
+fn is_digit(character: Option<char>, radix: u32) -> bool {
+    character.is_some_and(|character| character.is_digit(radix))
+}
+
+/// The offset after the digits and `_` separators starting at offset `n`.
+fn digits_end(stream: &mut impl PeekCharStream, mut n: usize, radix: u32) -> usize {
+    while peek_char(stream, n) == Some('_') || is_digit(peek_char(stream, n), radix) {
+        n += 1;
+    }
+    n
+}
+
+impl<Stream: PeekCharStream> Scan for Stream {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharStream, ToCharacterStream};
+
+    #[cfg(feature = "unicode-ident")]
+    #[test]
+    fn read_identifier_test() {
+        let mut character_stream = "_ünï2 9x".to_character_stream().peeky();
+
+        assert_eq!(
+            character_stream.read_identifier().unwrap().unwrap(),
+            "_ünï2"
+        );
+        assert!(character_stream.read_identifier().unwrap().is_none());
+        character_stream.skip_whitespace();
+        assert!(character_stream.read_identifier().unwrap().is_none());
+    }
+
+    #[test]
+    fn read_number_test() {
+        let mut character_stream = "-1_000 0xFF_ff 0b12 3.25e-2 1..2 7e 0o 9.x"
+            .to_character_stream()
+            .peeky();
+        let mut literals = vec![];
+
+        loop {
+            match character_stream.read_number() {
+                Some(literal) => literals.push((literal.text, literal.kind)),
+                None if character_stream.read_char().is_err() => break,
+                None => {}
+            }
+        }
+
+        let expected = [
+            ("-1_000", NumberKind::Decimal),
+            ("0xFF_ff", NumberKind::Hexadecimal),
+            ("0b1", NumberKind::Binary),
+            ("2", NumberKind::Decimal),
+            ("3.25e-2", NumberKind::Float),
+            ("1", NumberKind::Decimal),
+            ("2", NumberKind::Decimal),
+            ("7", NumberKind::Decimal),
+            ("0", NumberKind::Decimal),
+            ("9", NumberKind::Decimal),
+        ];
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(text, kind)| (text.to_string(), kind))
+            .collect();
+        assert_eq!(literals, expected);
+    }
+
+    #[test]
+    fn read_quoted_test() {
+        let stream = r#""a\"b\n" 'c\q' "open"#.to_character_stream().peeky();
+        let mut character_stream = crate::PositionedCharacterStream::new(stream);
+
+        assert_eq!(
+            character_stream.read_quoted('"', '\\').unwrap().unwrap(),
+            "a\"b\n"
+        );
+        assert!(character_stream.read_quoted('"', '\\').unwrap().is_none());
+        character_stream.skip_whitespace();
+        assert!(matches!(
+            character_stream.read_quoted('\'', '\\'),
+            Err(CharacterError::Unexpected {
+                found: Some('q'),
+                ..
+            })
+        ));
+        character_stream.take_while(|c| c != '"').unwrap();
+        assert_eq!(
+            character_stream
+                .read_quoted('"', '\\')
+                .unwrap_err()
+                .to_string(),
+            "Unterminated string at 1:16."
+        );
+    }
+
+    #[test]
+    fn read_balanced_test() {
+        let mut character_stream = r#"{a {b} "}\"" c} [[x]"#.to_character_stream().peeky();
+
+        assert_eq!(
+            character_stream
+                .read_balanced('{', '}', &['"'])
+                .unwrap()
+                .unwrap(),
+            r#"a {b} "}\"" c"#
+        );
+        assert!(character_stream
+            .read_balanced('[', ']', &[])
+            .unwrap()
+            .is_none());
+        character_stream.skip_whitespace();
+        assert!(matches!(
+            character_stream.read_balanced('[', ']', &[]),
+            Err(CharacterError::Unterminated { start: None, .. })
+        ));
+    }
+}
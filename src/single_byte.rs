@@ -0,0 +1,105 @@
+use std::io::Read;
+
+use crate::{read_bytes, CharStream, CharacterIterator, CharacterStreamResult, INTERRUPTED_MAX};
+
+/// Characters for the bytes `0x80` to `0x9F` in Windows-1252.
+///
+/// Bytes that Windows-1252 leaves undefined map to the matching C1 control, like the WHATWG encoding standard.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+/// Encodings that map every byte to a single character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SingleByteEncoding {
+    /// ISO-8859-1, where every byte is the code point of the same value.
+    Latin1,
+    /// Windows-1252, which is Latin-1 with printable characters in `0x80` to `0x9F`.
+    Windows1252,
+}
+
+impl SingleByteEncoding {
+    /// The character `byte` represents.
+    pub fn decode(&self, byte: u8) -> char {
+        match (self, byte) {
+            (SingleByteEncoding::Windows1252, 0x80..=0x9F) => {
+                WINDOWS_1252_HIGH[(byte - 0x80) as usize]
+            }
+            _ => byte as char,
+        }
+    }
+}
+
+/// Wrapper struct for any stream that holds text in a [SingleByteEncoding].
+///
+/// Every byte is a valid character, so reading never fails to decode.
+pub struct SingleByteCharacterStream<Reader: Read> {
+    /// The stream from which the incoming bytes are from.
+    pub stream: Reader,
+    /// The encoding of the bytes.
+    pub encoding: SingleByteEncoding,
+}
+
+impl<Reader: Read> SingleByteCharacterStream<Reader> {
+    /// Create a [SingleByteCharacterStream] from a stream.
+    pub fn new(stream: Reader, encoding: SingleByteEncoding) -> Self {
+        Self { stream, encoding }
+    }
+}
+
+impl<Reader: Read> CharStream for SingleByteCharacterStream<Reader> {
+    fn read_char(&mut self) -> CharacterStreamResult {
+        let bytes = read_bytes(&mut self.stream, 1)?;
+        Ok(self.encoding.decode(bytes[0]))
+    }
+
+    fn is_lossy(&self) -> bool {
+        false
+    }
+}
+
+impl<Reader: Read + std::fmt::Debug> std::fmt::Debug for SingleByteCharacterStream<Reader> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SingleByteCharacterStream")
+            .field("stream", &self.stream)
+            .field("encoding", &self.encoding)
+            .finish()
+    }
+}
+
+impl<Reader: Read> IntoIterator for SingleByteCharacterStream<Reader> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn single_byte_test() {
+        let bytes = b"caf\xE9 \x80\x93";
+        let decode = |encoding| -> String {
+            SingleByteCharacterStream::new(Cursor::new(bytes), encoding)
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(decode(SingleByteEncoding::Latin1), "caf\u{E9} \u{80}\u{93}");
+        assert_eq!(
+            decode(SingleByteEncoding::Windows1252),
+            "caf\u{E9} \u{20AC}\u{201C}"
+        );
+    }
+}
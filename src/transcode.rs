@@ -0,0 +1,146 @@
+use std::io::Write;
+
+use crate::{
+    CharStream, CharacterError, CharacterIterator, Endianness, UnicodeEncoding, INTERRUPTED_MAX,
+};
+
+/// The amount of encoded bytes buffered before they are written.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+impl UnicodeEncoding {
+    /// Append the encoding of `character` to `buffer`.
+    pub fn encode(&self, character: char, buffer: &mut Vec<u8>) {
+        match self {
+            UnicodeEncoding::Utf8 => {
+                buffer.extend_from_slice(character.encode_utf8(&mut [0u8; 4]).as_bytes())
+            }
+            UnicodeEncoding::Utf16(endianness) => {
+                for unit in character.encode_utf16(&mut [0u16; 2]) {
+                    buffer.extend_from_slice(&match endianness {
+                        Endianness::Little => unit.to_le_bytes(),
+                        Endianness::Big => unit.to_be_bytes(),
+                    });
+                }
+            }
+            UnicodeEncoding::Utf32(endianness) => buffer.extend_from_slice(&match endianness {
+                Endianness::Little => (character as u32).to_le_bytes(),
+                Endianness::Big => (character as u32).to_be_bytes(),
+            }),
+        }
+    }
+}
+
+/// Reads characters from a [CharStream] and writes them to a [Write] in another encoding.
+///
+/// Characters are processed incrementally, so only a small chunk of output is held in memory.
+pub struct Transcoder<Stream: CharStream, Writer: Write> {
+    /// The iterator the characters are read from.
+    pub iter: CharacterIterator<Stream>,
+    /// The writer the encoded characters are written to.
+    pub writer: Writer,
+    /// The encoding that is written.
+    pub encoding: UnicodeEncoding,
+}
+
+impl<Stream: CharStream, Writer: Write> Transcoder<Stream, Writer> {
+    /// Create a [Transcoder] writing the characters of `stream` to `writer` in `encoding`.
+    pub fn new(stream: Stream, writer: Writer, encoding: UnicodeEncoding) -> Self {
+        Self {
+            iter: CharacterIterator::new(stream, INTERRUPTED_MAX),
+            writer,
+            encoding,
+        }
+    }
+
+    /// Transcode the rest of the stream.
+    ///
+    /// Upon success, the amount of characters written is returned.
+    ///
+    /// Upon failure, the characters before the error have been written.
+    pub fn run(&mut self) -> Result<usize, CharacterError> {
+        let mut buffer = Vec::with_capacity(CHUNK_SIZE + 4);
+        let mut count = 0;
+        let mut result = Ok(());
+
+        for character in self.iter.by_ref() {
+            match character {
+                Ok(character) => {
+                    self.encoding.encode(character, &mut buffer);
+                    count += 1;
+                }
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            }
+
+            if buffer.len() >= CHUNK_SIZE {
+                write(&mut self.writer, &mut buffer)?;
+            }
+        }
+
+        write(&mut self.writer, &mut buffer)?;
+        self.writer
+            .flush()
+            .map_err(|error| CharacterError::IoError {
+                bytes: vec![],
+                error,
+            })?;
+
+        result.map(|_| count)
+    }
+
+    /// Return the writer.
+    pub fn into_writer(self) -> Writer {
+        self.writer
+    }
+}
+
+/// Write and clear `buffer`.
+fn write(writer: &mut impl Write, buffer: &mut Vec<u8>) -> Result<(), CharacterError> {
+    writer
+        .write_all(buffer)
+        .map_err(|error| CharacterError::IoError {
+            bytes: buffer.clone(),
+            error,
+        })?;
+    buffer.clear();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{ToCharacterStream, Utf16CharacterStream};
+
+    #[test]
+    fn transcoder_test() {
+        let text = "a\u{E9}\u{1F4BB}".repeat(5000);
+        let mut transcoder = Transcoder::new(
+            text.to_character_stream(),
+            vec![],
+            UnicodeEncoding::Utf16(Endianness::Big),
+        );
+
+        assert_eq!(transcoder.run().unwrap(), 15000);
+
+        let utf16 = transcoder.into_writer();
+        assert_eq!(
+            utf16,
+            text.encode_utf16()
+                .flat_map(u16::to_be_bytes)
+                .collect::<Vec<u8>>()
+        );
+
+        let mut transcoder = Transcoder::new(
+            Utf16CharacterStream::new(Cursor::new(utf16), Endianness::Big, false),
+            vec![],
+            UnicodeEncoding::Utf8,
+        );
+        transcoder.run().unwrap();
+        assert_eq!(transcoder.into_writer(), text.as_bytes());
+    }
+}
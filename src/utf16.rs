@@ -0,0 +1,274 @@
+use std::io::Read;
+
+use crate::{
+    read_bytes_into, CharStream, CharacterError, CharacterIterator, CharacterStreamResult,
+    INTERRUPTED_MAX,
+};
+
+/// Byte order of multi-byte code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Least significant byte first.
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+/// Wrapper struct for any stream that holds UTF-16 encoded text.
+///
+/// Surrogate pairs are combined into a single character.
+pub struct Utf16CharacterStream<Reader: Read> {
+    /// The stream from which the incoming bytes are from.
+    pub stream: Reader,
+    /// The byte order of the code units.
+    pub endianness: Endianness,
+    /// Whether or not we should care whether invalid code units are detected.
+    ///
+    /// If `true`, then unpaired surrogates will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+    /// Code unit that was read while looking for a low surrogate, but did not belong to the pair,
+    /// or the high surrogate itself if reading the low one failed.
+    pending: Option<Result<u16, CharacterError>>,
+}
+
+impl<Reader: Read> Utf16CharacterStream<Reader> {
+    /// Create a [Utf16CharacterStream] from a stream.
+    ///
+    /// Set `is_lossy` to `true` if you don't want to handle unpaired surrogates.
+    pub fn new(stream: Reader, endianness: Endianness, is_lossy: bool) -> Self {
+        Self {
+            stream,
+            endianness,
+            is_lossy,
+            pending: None,
+        }
+    }
+
+    /// Kinda builder pattern.
+    pub fn lossy(mut self, is_lossy: bool) -> Self {
+        self.is_lossy = is_lossy;
+        self
+    }
+
+    /// Reads a single code unit from the stream.
+    ///
+    /// A single byte left at the end of the stream is a [CharacterError::TruncatedSequence].
+    fn read_unit(&mut self) -> Result<u16, CharacterError> {
+        if let Some(unit) = self.pending.take() {
+            return unit;
+        }
+
+        let mut bytes = [0u8; 2];
+
+        match read_bytes_into(&mut self.stream, &mut bytes) {
+            Ok(0) => return Err(CharacterError::NoBytesRead),
+            Ok(1) => {
+                return Err(CharacterError::TruncatedSequence {
+                    bytes: vec![bytes[0]],
+                    expected: 2,
+                    got: 1,
+                })
+            }
+            Ok(_) => {}
+            Err((len, error)) => {
+                return Err(CharacterError::IoError {
+                    bytes: bytes[..len].to_vec(),
+                    error,
+                })
+            }
+        }
+
+        Ok(match self.endianness {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        })
+    }
+
+    /// The bytes that encode `units`.
+    fn unit_bytes(&self, units: &[u16]) -> Vec<u8> {
+        units
+            .iter()
+            .flat_map(|unit| match self.endianness {
+                Endianness::Little => unit.to_le_bytes(),
+                Endianness::Big => unit.to_be_bytes(),
+            })
+            .collect()
+    }
+
+    fn unpaired(&self, unit: u16) -> CharacterStreamResult {
+        if self.is_lossy {
+            Ok('\u{FFFD}')
+        } else {
+            Err(CharacterError::Surrogate {
+                bytes: self.unit_bytes(&[unit]),
+                code_point: unit,
+            })
+        }
+    }
+}
+
+impl<Reader: Read> CharStream for Utf16CharacterStream<Reader> {
+    /// Attempts to read a character from the stream.
+    ///
+    /// If `is_lossy` is set to `true`, then unpaired surrogates will be a U+FFFD.
+    ///
+    /// If `is_lossy` is set to `false`, then unpaired surrogates will be returned in addition to a parse error.
+    fn read_char(&mut self) -> CharacterStreamResult {
+        let unit = match self.read_unit() {
+            Err(CharacterError::TruncatedSequence { .. }) if self.is_lossy => {
+                return Ok('\u{FFFD}')
+            }
+            result => result?,
+        };
+
+        match unit {
+            0xD800..=0xDBFF => match self.read_unit() {
+                Ok(low @ 0xDC00..=0xDFFF) => {
+                    let code_point =
+                        0x10000 + (((unit as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+                    Ok(char::from_u32(code_point).unwrap_or('\u{FFFD}'))
+                }
+                Ok(other) => {
+                    self.pending = Some(Ok(other));
+                    self.unpaired(unit)
+                }
+                Err(CharacterError::NoBytesRead) => self.unpaired(unit),
+                Err(error @ CharacterError::TruncatedSequence { .. }) => {
+                    self.pending = Some(Err(error));
+                    self.unpaired(unit)
+                }
+                Err(error) => {
+                    self.pending = Some(Ok(unit));
+                    Err(error)
+                }
+            },
+            0xDC00..=0xDFFF => self.unpaired(unit),
+            _ => Ok(char::from_u32(unit as u32).unwrap_or('\u{FFFD}')),
+        }
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+}
+
+impl<Reader: Read + std::fmt::Debug> std::fmt::Debug for Utf16CharacterStream<Reader> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Utf16CharacterStream")
+            .field("stream", &self.stream)
+            .field("endianness", &self.endianness)
+            .field("is_lossy", &self.is_lossy)
+            .finish()
+    }
+}
+
+impl<Reader: Read> IntoIterator for Utf16CharacterStream<Reader> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn decode(bytes: &[u8], endianness: Endianness, is_lossy: bool) -> Vec<CharacterStreamResult> {
+        Utf16CharacterStream::new(Cursor::new(bytes), endianness, is_lossy)
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn utf16_test() {
+        let text = "a\u{E9}\u{1F4BB}";
+        let little: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let big: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+
+        for (bytes, endianness) in [(little, Endianness::Little), (big, Endianness::Big)] {
+            let read: String = decode(&bytes, endianness, false)
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(read, text);
+        }
+
+        // An unpaired high surrogate followed by a `b`.
+        let unpaired = [0x3D, 0xD8, b'b', 0x00];
+        let read = decode(&unpaired, Endianness::Little, false);
+        assert_eq!(
+            read[0].as_ref().unwrap_err().bytes(),
+            Some(&[0x3D, 0xD8][..])
+        );
+        assert_eq!(read[1].as_ref().unwrap(), &'b');
+
+        let read: String = decode(&unpaired, Endianness::Little, true)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, "\u{FFFD}b");
+    }
+
+    #[test]
+    fn truncated_test() {
+        let read = decode(b"a\x00b", Endianness::Little, false);
+        assert_eq!(read[0].as_ref().unwrap(), &'a');
+        assert!(matches!(
+            read[1],
+            Err(CharacterError::TruncatedSequence {
+                expected: 2,
+                got: 1,
+                ..
+            })
+        ));
+        assert_eq!(read.len(), 2);
+
+        // A high surrogate followed by a single byte.
+        let read = decode(&[0x3D, 0xD8, b'b'], Endianness::Little, false);
+        assert!(matches!(read[0], Err(CharacterError::Surrogate { .. })));
+        assert!(matches!(
+            read[1],
+            Err(CharacterError::TruncatedSequence { .. })
+        ));
+
+        let read: String = decode(&[0x3D, 0xD8, b'b'], Endianness::Little, true)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, "\u{FFFD}\u{FFFD}");
+    }
+
+    #[test]
+    fn interrupted_low_surrogate_test() {
+        struct Flaky<'a>(&'a [u8], bool);
+
+        impl Read for Flaky<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.len() == 2 && !self.1 {
+                    self.1 = true;
+                    return Err(std::io::ErrorKind::Interrupted.into());
+                }
+
+                self.0.read(buf)
+            }
+        }
+
+        let bytes: Vec<u8> = "\u{1F4BB}"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let read: String =
+            Utf16CharacterStream::new(Flaky(&bytes, false), Endianness::Little, false)
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(read, "\u{1F4BB}");
+    }
+}
@@ -0,0 +1,137 @@
+use std::io::Read;
+
+use crate::{
+    read_bytes, CharStream, CharacterError, CharacterIterator, CharacterStreamResult, Endianness,
+    INTERRUPTED_MAX,
+};
+
+/// Wrapper struct for any stream that holds UTF-32 encoded text.
+pub struct Utf32CharacterStream<Reader: Read> {
+    /// The stream from which the incoming bytes are from.
+    pub stream: Reader,
+    /// The byte order of the code units.
+    pub endianness: Endianness,
+    /// Whether or not we should care whether invalid code points are detected.
+    ///
+    /// If `true`, then invalid code points will be replaced with a U+FFFD.
+    ///
+    /// If `false`, then an error will be returned.
+    pub is_lossy: bool,
+}
+
+impl<Reader: Read> Utf32CharacterStream<Reader> {
+    /// Create a [Utf32CharacterStream] from a stream.
+    ///
+    /// Set `is_lossy` to `true` if you don't want to handle invalid code points.
+    pub fn new(stream: Reader, endianness: Endianness, is_lossy: bool) -> Self {
+        Self {
+            stream,
+            endianness,
+            is_lossy,
+        }
+    }
+
+    /// Kinda builder pattern.
+    pub fn lossy(mut self, is_lossy: bool) -> Self {
+        self.is_lossy = is_lossy;
+        self
+    }
+}
+
+impl<Reader: Read> CharStream for Utf32CharacterStream<Reader> {
+    /// Attempts to read a character from the stream.
+    ///
+    /// If `is_lossy` is set to `true`, then invalid code points will be a U+FFFD.
+    ///
+    /// If `is_lossy` is set to `false`, then invalid code points will be returned in addition to a parse error.
+    fn read_char(&mut self) -> CharacterStreamResult {
+        let bytes = read_bytes(&mut self.stream, 4)?;
+        let unit = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let code_point = match self.endianness {
+            Endianness::Little => u32::from_le_bytes(unit),
+            Endianness::Big => u32::from_be_bytes(unit),
+        };
+
+        match char::from_u32(code_point) {
+            Some(character) => Ok(character),
+            None if self.is_lossy => Ok('\u{FFFD}'),
+            None if code_point > 0x10FFFF => Err(CharacterError::OutOfRange { bytes, code_point }),
+            None => Err(CharacterError::Surrogate {
+                bytes,
+                code_point: code_point as u16,
+            }),
+        }
+    }
+
+    fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+}
+
+impl<Reader: Read + std::fmt::Debug> std::fmt::Debug for Utf32CharacterStream<Reader> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Utf32CharacterStream")
+            .field("stream", &self.stream)
+            .field("endianness", &self.endianness)
+            .field("is_lossy", &self.is_lossy)
+            .finish()
+    }
+}
+
+impl<Reader: Read> IntoIterator for Utf32CharacterStream<Reader> {
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    type IntoIter = CharacterIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CharacterIterator::new(self, INTERRUPTED_MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn utf32_test() {
+        let text = "a\u{E9}\u{1F4BB}";
+        let little: Vec<u8> = text
+            .chars()
+            .flat_map(|c| (c as u32).to_le_bytes())
+            .collect();
+        let big: Vec<u8> = text
+            .chars()
+            .flat_map(|c| (c as u32).to_be_bytes())
+            .collect();
+
+        for (bytes, endianness) in [(little, Endianness::Little), (big, Endianness::Big)] {
+            let read: String = Utf32CharacterStream::new(Cursor::new(bytes), endianness, false)
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(read, text);
+        }
+
+        let mut character_stream = Utf32CharacterStream::new(
+            Cursor::new(b"\x00\xD8\x00\x00\x00\x00\x11\x00"),
+            Endianness::Little,
+            false,
+        );
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::Surrogate {
+                code_point: 0xD800,
+                ..
+            })
+        ));
+        assert!(matches!(
+            character_stream.read_char(),
+            Err(CharacterError::OutOfRange {
+                code_point: 0x110000,
+                ..
+            })
+        ));
+    }
+}